@@ -38,7 +38,7 @@ use sp_runtime::{
 use sp_state_machine::OverlayedChanges;
 // Frontier
 use fc_rpc_core::types::*;
-use fp_evm::{ExecutionInfo, ExecutionInfoV2};
+use fp_evm::{ExecutionInfo, ExecutionInfoV2, StateOverride};
 use fp_rpc::{EthereumRuntimeRPCApi, RuntimeStorageOverride};
 use fp_storage::constants::{EVM_ACCOUNT_CODES, EVM_ACCOUNT_STORAGES, PALLET_EVM};
 
@@ -291,6 +291,34 @@ where
 					};
 
 					Ok(Bytes(value))
+				} else if api_version >= 6 {
+					// Post-london + access list + runtime state overrides support
+					let info = api
+						.call(
+							substrate_hash,
+							from.unwrap_or_default(),
+							to,
+							data,
+							value.unwrap_or_default(),
+							gas_limit,
+							max_fee_per_gas,
+							max_priority_fee_per_gas,
+							nonce,
+							false,
+							Some(
+								access_list
+									.unwrap_or_default()
+									.into_iter()
+									.map(|item| (item.address, item.storage_keys))
+									.collect(),
+							),
+							state_overrides.map(runtime_state_overrides),
+						)
+						.map_err(|err| internal_err(format!("runtime error: {err}")))?
+						.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+					error_on_execution_failure(&info.exit_reason, &info.value)?;
+					Ok(Bytes(info.value))
 				} else {
 					Err(internal_err("failed to retrieve Runtime Api version"))
 				}
@@ -374,6 +402,36 @@ where
 				} else if api_version == 5 {
 					// Post-london + access list support
 					let access_list = access_list.unwrap_or_default();
+					#[allow(deprecated)]
+					let info = api.create_before_version_6(
+						substrate_hash,
+						from.unwrap_or_default(),
+						data,
+						value.unwrap_or_default(),
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						false,
+						Some(
+							access_list
+								.into_iter()
+								.map(|item| (item.address, item.storage_keys))
+								.collect(),
+						),
+					)
+					.map_err(|err| internal_err(format!("runtime error: {err}")))?
+					.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+					error_on_execution_failure(&info.exit_reason, &[])?;
+
+					let code = api
+						.account_code_at(substrate_hash, info.value)
+						.map_err(|err| internal_err(format!("runtime error: {err}")))?;
+					Ok(Bytes(code))
+				} else if api_version >= 6 {
+					// Post-london + access list + runtime state overrides support
+					let access_list = access_list.unwrap_or_default();
 					let info = api
 						.create(
 							substrate_hash,
@@ -391,6 +449,7 @@ where
 									.map(|item| (item.address, item.storage_keys))
 									.collect(),
 							),
+							state_overrides.map(runtime_state_overrides),
 						)
 						.map_err(|err| internal_err(format!("runtime error: {err}")))?
 						.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
@@ -634,9 +693,35 @@ where
 							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
 
 							(info.exit_reason, info.value, info.used_gas)
-						} else {
+						} else if api_version == 5 {
 							// Post-london + access list support
 							let access_list = access_list.unwrap_or_default();
+							#[allow(deprecated)]
+							let info = api.call_before_version_6(
+								substrate_hash,
+								from.unwrap_or_default(),
+								to,
+								data,
+								value.unwrap_or_default(),
+								gas_limit,
+								max_fee_per_gas,
+								max_priority_fee_per_gas,
+								None,
+								estimate_mode,
+								Some(
+									access_list
+										.into_iter()
+										.map(|item| (item.address, item.storage_keys))
+										.collect(),
+								),
+							)
+							.map_err(|err| internal_err(format!("runtime error: {err}")))?
+							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+							(info.exit_reason, info.value, info.used_gas.effective)
+						} else {
+							// Post-london + access list + runtime state overrides support
+							let access_list = access_list.unwrap_or_default();
 							let info = api.call(
 								substrate_hash,
 								from.unwrap_or_default(),
@@ -654,6 +739,7 @@ where
 										.map(|item| (item.address, item.storage_keys))
 										.collect(),
 								),
+								None,
 							)
 							.map_err(|err| internal_err(format!("runtime error: {err}")))?
 							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
@@ -722,9 +808,34 @@ where
 							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
 
 							(info.exit_reason, Vec::new(), info.used_gas)
-						} else {
+						} else if api_version == 5 {
 							// Post-london + access list support
 							let access_list = access_list.unwrap_or_default();
+							#[allow(deprecated)]
+							let info = api.create_before_version_6(
+								substrate_hash,
+								from.unwrap_or_default(),
+								data,
+								value.unwrap_or_default(),
+								gas_limit,
+								max_fee_per_gas,
+								max_priority_fee_per_gas,
+								None,
+								estimate_mode,
+								Some(
+									access_list
+										.into_iter()
+										.map(|item| (item.address, item.storage_keys))
+										.collect(),
+								),
+							)
+							.map_err(|err| internal_err(format!("runtime error: {err}")))?
+							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+							(info.exit_reason, Vec::new(), info.used_gas.effective)
+						} else {
+							// Post-london + access list + runtime state overrides support
+							let access_list = access_list.unwrap_or_default();
 							let info = api.create(
 								substrate_hash,
 								from.unwrap_or_default(),
@@ -741,6 +852,7 @@ where
 										.map(|item| (item.address, item.storage_keys))
 										.collect(),
 								),
+								None,
 							)
 							.map_err(|err| internal_err(format!("runtime error: {err}")))?
 							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
@@ -957,6 +1069,29 @@ where
 	}
 }
 
+/// Converts RPC state overrides to the ones applied by the runtime `call` and `create` APIs.
+fn runtime_state_overrides(
+	state_overrides: BTreeMap<H160, CallStateOverride>,
+) -> Vec<(H160, StateOverride)> {
+	state_overrides
+		.into_iter()
+		.map(|(address, state_override)| {
+			let state_override = StateOverride {
+				balance: state_override.balance,
+				nonce: state_override.nonce,
+				code: state_override.code.map(|code| code.into_vec()),
+				state: state_override
+					.state
+					.map(|state| state.into_iter().collect()),
+				state_diff: state_override
+					.state_diff
+					.map(|state_diff| state_diff.into_iter().collect()),
+			};
+			(address, state_override)
+		})
+		.collect()
+}
+
 pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> RpcResult<()> {
 	match reason {
 		ExitReason::Succeed(_) => Ok(()),
@@ -48,6 +48,11 @@ impl Geth {
 						"max priority fee per gas higher than max fee per gas".into()
 					}
 					VError::InvalidFeeInput => "invalid fee input".into(),
+					VError::SenderHasCode => "sender not an eoa".into(),
+					VError::MaxInitCodeSizeExceeded => "max initcode size exceeded".into(),
+					VError::GasQuotaExceeded => "gas quota exceeded".into(),
+					VError::CreateOriginNotAllowed => "sender not allowed to create".into(),
+					VError::CallOriginNotAllowed => "sender not allowed to call".into(),
 					_ => "transaction validation error".into(),
 				},
 				_ => "unknown error".into(),
@@ -19,6 +19,8 @@
 //!
 //! The BaseFee pallet is responsible for managing the `BaseFeePerGas` value.
 //! This pallet can dynamically adjust the `BaseFeePerGas` by utilizing `Elasticity`.
+//! The block utilization driving the adjustment is provided by [`Config::BlockUtilization`],
+//! and the resulting `BaseFeePerGas` can be bounded by governance.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::comparison_chain)]
@@ -27,6 +29,7 @@
 #[cfg(test)]
 mod tests;
 
+use core::marker::PhantomData;
 use frame_support::{traits::Get, weights::Weight};
 use sp_core::U256;
 use sp_runtime::Permill;
@@ -37,6 +40,19 @@ pub trait BaseFeeThreshold {
 	fn upper() -> Permill;
 }
 
+pub use fp_evm::BlockUtilization;
+
+/// [`BlockUtilization`] based on the ref time of the consumed block weight.
+pub struct BlockWeightUtilization<T>(PhantomData<T>);
+impl<T: frame_system::Config> BlockUtilization for BlockWeightUtilization<T> {
+	fn utilization() -> Permill {
+		let weight = <frame_system::Pallet<T>>::block_weight();
+		let max_weight = T::BlockWeights::get().max_block;
+
+		Permill::from_rational(weight.total().ref_time(), max_weight.ref_time())
+	}
+}
+
 pub use self::pallet::*;
 
 #[frame_support::pallet]
@@ -53,6 +69,9 @@ pub mod pallet {
 		type RuntimeEvent: From<Event> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// Lower and upper bounds for increasing / decreasing `BaseFeePerGas`.
 		type Threshold: BaseFeeThreshold;
+		/// Utilization of the current block, e.g. [`BlockWeightUtilization`] or the gas used per
+		/// block by `pallet-evm`.
+		type BlockUtilization: BlockUtilization;
 		type DefaultBaseFeePerGas: Get<U256>;
 		type DefaultElasticity: Get<Permill>;
 	}
@@ -109,12 +128,28 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type Elasticity<T> = StorageValue<_, Permill, ValueQuery, DefaultElasticity<T>>;
 
+	/// Lower bound of `BaseFeePerGas`, if any.
+	#[pallet::storage]
+	pub type MinBaseFeePerGas<T> = StorageValue<_, U256, OptionQuery>;
+
+	/// Upper bound of `BaseFeePerGas`, if any.
+	#[pallet::storage]
+	pub type MaxBaseFeePerGas<T> = StorageValue<_, U256, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event {
 		NewBaseFeePerGas { fee: U256 },
 		BaseFeeOverflow,
 		NewElasticity { elasticity: Permill },
+		NewMinBaseFeePerGas { fee: Option<U256> },
+		NewMaxBaseFeePerGas { fee: Option<U256> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The minimum base fee is greater than the maximum base fee.
+		InvalidBaseFeeBounds,
 	}
 
 	#[pallet::hooks]
@@ -123,9 +158,10 @@ pub mod pallet {
 			// Register the Weight used on_finalize.
 			// 	- One storage read to get the block_weight.
 			// 	- One storage read to get the Elasticity.
+			// 	- Two storage reads to get the MinBaseFeePerGas and MaxBaseFeePerGas.
 			// 	- One write to BaseFeePerGas.
 			let db_weight = <T as frame_system::Config>::DbWeight::get();
-			db_weight.reads_writes(2, 1)
+			db_weight.reads_writes(4, 1)
 		}
 
 		fn on_finalize(_n: BlockNumberFor<T>) {
@@ -142,13 +178,8 @@ pub mod pallet {
 			// If we go above the `target`, the base fee is linearly increased by the Elasticity delta of upper~target.
 			// The base fee is fully increased (default 12.5%) if the block is upper full (default 100%).
 			// The base fee is fully decreased (default 12.5%) if the block is lower empty (default 0%).
-			let weight = <frame_system::Pallet<T>>::block_weight();
-			let max_weight = <<T as frame_system::Config>::BlockWeights>::get().max_block;
-
-			// We convert `weight` into block fullness and ensure we are within the lower and upper bound.
-			let weight_used =
-				Permill::from_rational(weight.total().ref_time(), max_weight.ref_time())
-					.clamp(lower, upper);
+			// We get the block fullness and ensure we are within the lower and upper bound.
+			let weight_used = T::BlockUtilization::utilization().clamp(lower, upper);
 			// After clamp `weighted_used` is always between `lower` and `upper`.
 			// We scale the block fullness range to the lower/upper range, and the usage represents the
 			// actual percentage within this new scale.
@@ -167,7 +198,8 @@ pub mod pallet {
 						let increase = scaled_basefee
 							.checked_div(U256::from(1_000_000))
 							.unwrap_or_else(U256::zero);
-						*bf = bf.saturating_add(increase);
+						// Keep the base fee within the bounds set by governance.
+						*bf = Self::clamp_base_fee_per_gas(bf.saturating_add(increase));
 					} else {
 						Self::deposit_event(Event::BaseFeeOverflow);
 					}
@@ -190,11 +222,13 @@ pub mod pallet {
 							.unwrap_or(default_base_fee)
 							.checked_div(U256::from(1_000_000))
 							.unwrap_or(default_base_fee);
-						if bf.saturating_sub(decrease) >= lowest_base_fee {
-							*bf = bf.saturating_sub(decrease);
+						let decreased = if bf.saturating_sub(decrease) >= lowest_base_fee {
+							bf.saturating_sub(decrease)
 						} else {
-							*bf = lowest_base_fee;
-						}
+							lowest_base_fee
+						};
+						// Keep the base fee within the bounds set by governance.
+						*bf = Self::clamp_base_fee_per_gas(decreased);
 					} else {
 						Self::deposit_event(Event::BaseFeeOverflow);
 					}
@@ -222,6 +256,23 @@ pub mod pallet {
 			Self::deposit_event(Event::NewElasticity { elasticity });
 			Ok(())
 		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 3).ref_time())]
+		pub fn set_base_fee_bounds(
+			origin: OriginFor<T>,
+			min: Option<U256>,
+			max: Option<U256>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			if let (Some(min), Some(max)) = (min, max) {
+				ensure!(min <= max, Error::<T>::InvalidBaseFeeBounds);
+			}
+			let _ = Self::set_base_fee_bounds_inner(min, max);
+			Self::deposit_event(Event::NewMinBaseFeePerGas { fee: min });
+			Self::deposit_event(Event::NewMaxBaseFeePerGas { fee: max });
+			Ok(())
+		}
 	}
 }
 
@@ -240,4 +291,22 @@ impl<T: Config> Pallet<T> {
 		<Elasticity<T>>::put(value);
 		T::DbWeight::get().writes(1)
 	}
+	/// Set the bounds of `BaseFeePerGas`, clamping the current base fee to them.
+	pub fn set_base_fee_bounds_inner(min: Option<U256>, max: Option<U256>) -> Weight {
+		<MinBaseFeePerGas<T>>::set(min);
+		<MaxBaseFeePerGas<T>>::set(max);
+		<BaseFeePerGas<T>>::mutate(|bf| *bf = Self::clamp_base_fee_per_gas(*bf));
+		T::DbWeight::get().reads_writes(1, 3)
+	}
+	/// Clamp the given base fee to the bounds set by governance.
+	pub fn clamp_base_fee_per_gas(value: U256) -> U256 {
+		let value = match <MaxBaseFeePerGas<T>>::get() {
+			Some(max) => value.min(max),
+			None => value,
+		};
+		match <MinBaseFeePerGas<T>>::get() {
+			Some(min) => value.max(min),
+			None => value,
+		}
+	}
 }
@@ -16,7 +16,7 @@
 // limitations under the License.
 
 use frame_support::{
-	assert_ok, derive_impl,
+	assert_noop, assert_ok, derive_impl,
 	dispatch::DispatchClass,
 	parameter_types,
 	traits::{ConstU32, OnFinalize},
@@ -68,6 +68,15 @@ impl frame_system::Config for Test {
 parameter_types! {
 	pub DefaultBaseFeePerGas: U256 = U256::from(100_000_000_000_u128);
 	pub DefaultElasticity: Permill = Permill::from_parts(125_000);
+	pub static Utilization: Option<Permill> = None;
+}
+
+/// Block weight utilization, unless overridden by `Utilization`.
+pub struct TestBlockUtilization;
+impl BlockUtilization for TestBlockUtilization {
+	fn utilization() -> Permill {
+		Utilization::get().unwrap_or_else(BlockWeightUtilization::<Test>::utilization)
+	}
 }
 
 pub struct BaseFeeThreshold;
@@ -86,6 +95,7 @@ impl BaseFeeThresholdT for BaseFeeThreshold {
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Threshold = BaseFeeThreshold;
+	type BlockUtilization = TestBlockUtilization;
 	type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
 	type DefaultElasticity = DefaultElasticity;
 }
@@ -93,7 +103,7 @@ impl Config for Test {
 frame_support::construct_runtime!(
 	pub enum Test {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
-		BaseFee: pallet_base_fee::{Pallet, Call, Storage, Event},
+		BaseFee: pallet_base_fee::{Pallet, Call, Storage, Event, Error<T>},
 	}
 );
 
@@ -303,3 +313,87 @@ fn set_elasticity_dispatchable() {
 		assert_eq!(Elasticity::<Test>::get(), Permill::from_parts(1_000));
 	});
 }
+
+#[test]
+fn should_use_block_utilization() {
+	let base_fee = U256::from(1_000_000_000);
+	new_test_ext(Some(base_fee), None).execute_with(|| {
+		// Full block, regardless of the consumed block weight.
+		Utilization::set(Some(Permill::one()));
+		BaseFee::on_finalize(System::block_number());
+		Utilization::set(None);
+		// Expect the base fee to increase by 12.5%.
+		assert_eq!(BaseFeePerGas::<Test>::get(), U256::from(1125000000));
+	});
+}
+
+#[test]
+fn should_clamp_base_fee_to_bounds() {
+	new_test_ext(None, None).execute_with(|| {
+		assert_ok!(BaseFee::set_base_fee_bounds(
+			RuntimeOrigin::root(),
+			Some(U256::from(60_000_000_000_u128)),
+			Some(U256::from(110_000_000_000_u128))
+		));
+
+		// Full block, the 12.5% increase is capped by the upper bound.
+		Utilization::set(Some(Permill::one()));
+		BaseFee::on_finalize(System::block_number());
+		assert_eq!(
+			BaseFeePerGas::<Test>::get(),
+			U256::from(110_000_000_000_u128)
+		);
+
+		// Empty blocks, the base fee can't go below the lower bound.
+		Utilization::set(Some(Permill::zero()));
+		for _ in 0..10 {
+			BaseFee::on_finalize(System::block_number());
+			System::set_block_number(System::block_number() + 1);
+		}
+		Utilization::set(None);
+		assert_eq!(
+			BaseFeePerGas::<Test>::get(),
+			U256::from(60_000_000_000_u128)
+		);
+	});
+}
+
+#[test]
+fn should_clamp_base_fee_when_setting_bounds() {
+	new_test_ext(Some(U256::from(100)), None).execute_with(|| {
+		assert_ok!(BaseFee::set_base_fee_bounds(
+			RuntimeOrigin::root(),
+			Some(U256::from(200)),
+			None
+		));
+		assert_eq!(BaseFeePerGas::<Test>::get(), U256::from(200));
+
+		assert_ok!(BaseFee::set_base_fee_bounds(
+			RuntimeOrigin::root(),
+			None,
+			Some(U256::from(150))
+		));
+		assert_eq!(BaseFeePerGas::<Test>::get(), U256::from(150));
+	});
+}
+
+#[test]
+fn set_base_fee_bounds_dispatchable() {
+	new_test_ext(None, None).execute_with(|| {
+		assert_noop!(
+			BaseFee::set_base_fee_bounds(
+				RuntimeOrigin::root(),
+				Some(U256::from(2)),
+				Some(U256::from(1))
+			),
+			Error::<Test>::InvalidBaseFeeBounds
+		);
+		assert_ok!(BaseFee::set_base_fee_bounds(
+			RuntimeOrigin::root(),
+			Some(U256::from(1)),
+			None
+		));
+		assert_eq!(MinBaseFeePerGas::<Test>::get(), Some(U256::from(1)));
+		assert_eq!(MaxBaseFeePerGas::<Test>::get(), None);
+	});
+}
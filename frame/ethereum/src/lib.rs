@@ -204,11 +204,13 @@ pub mod pallet {
 		type PostLogContent: Get<PostLogContent>;
 		/// The maximum length of the extra data in the Executed event.
 		type ExtraDataLength: Get<u32>;
+		/// The maximum distance between the nonce of a pool transaction and the sender nonce.
+		type MaxNonceGap: Get<u64>;
 	}
 
 	pub mod config_preludes {
 		use super::*;
-		use frame_support::{derive_impl, parameter_types};
+		use frame_support::{derive_impl, parameter_types, traits::ConstU64};
 
 		pub struct TestDefaultConfig;
 
@@ -229,6 +231,7 @@ pub mod pallet {
 			type StateRoot = IntermediateStateRoot<Self::Version>;
 			type PostLogContent = PostBlockAndTxnHashes;
 			type ExtraDataLength = ConstU32<30>;
+			type MaxNonceGap = ConstU64<64>;
 		}
 	}
 
@@ -526,14 +529,27 @@ impl<T: Config> Pallet<T> {
 		let (weight_limit, proof_size_base_cost) = Self::transaction_weight(&transaction_data);
 		let (base_fee, _) = T::FeeCalculator::min_gas_price();
 		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
+		let evm_config = pallet_evm::Pallet::<T>::config_with_code_limits(T::config());
+
+		// The origin filters are enforced by the runner on execution, where filtered out senders
+		// fail without paying any fee. Also check them here, otherwise their transactions would be
+		// included in the mempool and pollute the mempool forever.
+		let target = match transaction_data.action {
+			TransactionAction::Call(target) => Some(target),
+			TransactionAction::Create => None,
+		};
+		pallet_evm::Pallet::<T>::ensure_origin_allowed(&origin, target)
+			.map_err(|e| InvalidTransactionWrapper::from(e).0)?;
 
 		let _ = CheckEvmTransaction::<InvalidTransactionWrapper>::new(
 			CheckEvmTransactionConfig {
-				evm_config: T::config(),
+				evm_config: &evm_config,
 				block_gas_limit: T::BlockGasLimit::get(),
 				base_fee,
 				chain_id: T::ChainId::get(),
 				is_transactional: true,
+				is_estimate: false,
+				max_nonce_gap: Some(T::MaxNonceGap::get().into()),
 			},
 			transaction_data.clone().into(),
 			weight_limit,
@@ -543,6 +559,7 @@ impl<T: Config> Pallet<T> {
 		.and_then(|v| v.with_chain_id())
 		.and_then(|v| v.with_base_fee())
 		.and_then(|v| v.with_balance_for(&who))
+		.and_then(|v| v.with_gas_quota(pallet_evm::Pallet::<T>::gas_quota_remaining(&origin)))
 		.map_err(|e| e.0)?;
 
 		// EIP-3607: https://eips.ethereum.org/EIPS/eip-3607
@@ -551,11 +568,14 @@ impl<T: Config> Pallet<T> {
 		// This check should be done on the transaction validation (here) **and**
 		// on transaction execution, otherwise a contract tx will be included in
 		// the mempool and pollute the mempool forever.
-		if !pallet_evm::AccountCodes::<T>::get(origin).is_empty() {
+		//
+		// Pool consumers already rely on `BadSigner` for this rejection, so keep it here;
+		// `SenderHasCode` is only reported by the pallet-evm runner.
+		if pallet_evm::AccountCodes::<T>::decode_len(origin).unwrap_or(0) > 0 {
 			return Err(InvalidTransaction::BadSigner.into());
 		}
 
-		let priority = match (
+		let effective_tip = match (
 			transaction_data.gas_price,
 			transaction_data.max_fee_per_gas,
 			transaction_data.max_priority_fee_per_gas,
@@ -563,19 +583,21 @@ impl<T: Config> Pallet<T> {
 			// Legacy or EIP-2930 transaction.
 			// Handle priority here. On legacy transaction everything in gas_price except
 			// the current base_fee is considered a tip to the miner and thus the priority.
-			(Some(gas_price), None, None) => {
-				gas_price.saturating_sub(base_fee).unique_saturated_into()
-			}
+			(Some(gas_price), None, None) => gas_price.saturating_sub(base_fee),
 			// EIP-1559 transaction without tip.
-			(None, Some(_), None) => 0,
+			(None, Some(_), None) => U256::zero(),
 			// EIP-1559 transaction with tip.
 			(None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => max_fee_per_gas
 				.saturating_sub(base_fee)
-				.min(max_priority_fee_per_gas)
-				.unique_saturated_into(),
+				.min(max_priority_fee_per_gas),
 			// Unreachable because already validated. Gracefully handle.
 			_ => return Err(InvalidTransaction::Payment.into()),
 		};
+		// The priority is the total tip the transaction pays to the block author, so that
+		// transactions paying more are included first.
+		let priority = effective_tip
+			.saturating_mul(transaction_data.gas_limit)
+			.unique_saturated_into();
 
 		// The tag provides and requires must be filled correctly according to the nonce.
 		let mut builder = ValidTransactionBuilder::default()
@@ -883,6 +905,25 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Validate and apply `transaction`, returning the steps of its EVM execution, see
+	/// [`pallet_evm::tracing`]. Backs [`fp_rpc::EthereumTracingRuntimeApi`].
+	#[cfg(feature = "evm-tracing")]
+	pub fn trace_transaction(
+		transaction: Transaction,
+		config: &fp_evm::TraceConfig,
+	) -> Result<Vec<fp_evm::TraceStep>, sp_runtime::DispatchError> {
+		let source = Self::recover_signer(&transaction).ok_or(Error::<T>::InvalidSignature)?;
+		Self::validate_transaction_in_block(source, &transaction)
+			.map_err(|e| sp_runtime::DispatchError::Other(e.into()))?;
+
+		let (result, steps) = pallet_evm::tracing::trace(config, || {
+			Self::apply_validated_transaction(source, transaction)
+		});
+		result.map_err(|e| e.error)?;
+
+		Ok(steps)
+	}
+
 	/// Validate an Ethereum transaction already in block
 	///
 	/// This function must be called during the pre-dispatch phase
@@ -895,14 +936,17 @@ impl<T: Config> Pallet<T> {
 		let (weight_limit, proof_size_base_cost) = Self::transaction_weight(&transaction_data);
 		let (base_fee, _) = T::FeeCalculator::min_gas_price();
 		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
+		let evm_config = pallet_evm::Pallet::<T>::config_with_code_limits(T::config());
 
 		let _ = CheckEvmTransaction::<InvalidTransactionWrapper>::new(
 			CheckEvmTransactionConfig {
-				evm_config: T::config(),
+				evm_config: &evm_config,
 				block_gas_limit: T::BlockGasLimit::get(),
 				base_fee,
 				chain_id: T::ChainId::get(),
 				is_transactional: true,
+				is_estimate: false,
+				max_nonce_gap: None,
 			},
 			transaction_data.into(),
 			weight_limit,
@@ -912,6 +956,7 @@ impl<T: Config> Pallet<T> {
 		.and_then(|v| v.with_chain_id())
 		.and_then(|v| v.with_base_fee())
 		.and_then(|v| v.with_balance_for(&who))
+		.and_then(|v| v.with_gas_quota(pallet_evm::Pallet::<T>::gas_quota_remaining(&origin)))
 		.map_err(|e| TransactionValidityError::Invalid(e.0))?;
 
 		Ok(())
@@ -1047,9 +1092,28 @@ impl From<TransactionValidationError> for InvalidTransactionWrapper {
 			TransactionValidationError::InvalidSignature => InvalidTransactionWrapper(
 				InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8),
 			),
+			TransactionValidationError::SenderHasCode => {
+				InvalidTransactionWrapper(InvalidTransaction::BadSigner)
+			}
+			TransactionValidationError::MaxInitCodeSizeExceeded => {
+				InvalidTransactionWrapper(InvalidTransaction::Custom(
+					TransactionValidationError::MaxInitCodeSizeExceeded as u8,
+				))
+			}
 			TransactionValidationError::GasPriceTooLow => InvalidTransactionWrapper(
 				InvalidTransaction::Custom(TransactionValidationError::GasPriceTooLow as u8),
 			),
+			TransactionValidationError::GasQuotaExceeded => InvalidTransactionWrapper(
+				InvalidTransaction::Custom(TransactionValidationError::GasQuotaExceeded as u8),
+			),
+			TransactionValidationError::CreateOriginNotAllowed => {
+				InvalidTransactionWrapper(InvalidTransaction::Custom(
+					TransactionValidationError::CreateOriginNotAllowed as u8,
+				))
+			}
+			TransactionValidationError::CallOriginNotAllowed => InvalidTransactionWrapper(
+				InvalidTransaction::Custom(TransactionValidationError::CallOriginNotAllowed as u8),
+			),
 			TransactionValidationError::UnknownError => InvalidTransactionWrapper(
 				InvalidTransaction::Custom(TransactionValidationError::UnknownError as u8),
 			),
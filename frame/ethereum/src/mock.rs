@@ -38,7 +38,7 @@ frame_support::construct_runtime! {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
-		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>},
+		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>, HoldReason},
 		Ethereum: crate::{Pallet, Call, Storage, Event, Origin},
 	}
 }
@@ -210,6 +210,39 @@ fn transaction_with_to_low_nonce_should_not_work() {
 	});
 }
 
+#[test]
+fn transaction_priority_is_effective_tip_scaled_by_gas_limit() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let priority = |max_fee_per_gas: u64, max_priority_fee_per_gas: u64| {
+			let mut transaction = eip1559_erc20_creation_unsigned_transaction();
+			transaction.max_fee_per_gas = U256::from(max_fee_per_gas);
+			transaction.max_priority_fee_per_gas = U256::from(max_priority_fee_per_gas);
+
+			let signed = transaction.sign(&alice.private_key, None);
+			let call = crate::Call::<Test>::transact {
+				transaction: signed,
+			};
+			let source = call.check_self_contained().unwrap().unwrap();
+			let extrinsic = CheckedExtrinsic::<u64, _, SignedExtra, H160> {
+				signed: fp_self_contained::CheckedSignature::SelfContained(source),
+				function: RuntimeCall::Ethereum(call.clone()),
+			};
+			call.validate_self_contained(&source, &extrinsic.get_dispatch_info(), 0)
+				.unwrap()
+				.unwrap()
+				.priority
+		};
+
+		// The base fee is 1, the tip is capped by the max priority fee.
+		assert_eq!(priority(4, 2), 2 * 0x100000);
+		// The tip is capped by what is left of the max fee after the base fee.
+		assert_eq!(priority(2, 2), 0x100000);
+	});
+}
+
 #[test]
 fn transaction_with_to_hight_nonce_should_fail_in_block() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -22,6 +22,7 @@ use evm::{ExitReason, ExitRevert, ExitSucceed};
 use fp_ethereum::{TransactionData, ValidatedTransaction};
 use frame_support::{
 	dispatch::{DispatchClass, GetDispatchInfo},
+	traits::Get,
 	weights::Weight,
 };
 use pallet_evm::AddressMapping;
@@ -168,6 +169,61 @@ fn transaction_with_to_hight_nonce_should_fail_in_block() {
 	});
 }
 
+#[test]
+fn transaction_with_nonce_gap_too_high_should_not_work_in_pool() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let max_nonce_gap = <Test as crate::Config>::MaxNonceGap::get();
+		let validate = |nonce: u64| {
+			let mut transaction = legacy_erc20_creation_unsigned_transaction();
+			transaction.nonce = U256::from(nonce);
+
+			let signed = transaction.sign(&alice.private_key);
+			let call = crate::Call::<Test>::transact {
+				transaction: signed,
+			};
+			let source = call.check_self_contained().unwrap().unwrap();
+			let extrinsic = CheckedExtrinsic::<_, _, SignedExtra, _> {
+				signed: fp_self_contained::CheckedSignature::SelfContained(source),
+				function: RuntimeCall::Ethereum(call.clone()),
+			};
+			call.validate_self_contained(&source, &extrinsic.get_dispatch_info(), 0)
+				.unwrap()
+		};
+
+		assert_ok!(validate(max_nonce_gap));
+		assert_err!(
+			validate(max_nonce_gap + 1),
+			TransactionValidityError::Invalid(InvalidTransaction::Future)
+		);
+	});
+}
+
+#[test]
+fn transaction_from_sender_with_code_should_not_work_in_pool() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		pallet_evm::AccountCodes::<Test>::insert(alice.address, vec![0x00]);
+
+		let transaction = legacy_erc20_creation_transaction(alice);
+		let call = crate::Call::<Test>::transact { transaction };
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = CheckedExtrinsic::<_, _, SignedExtra, _> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: RuntimeCall::Ethereum(call.clone()),
+		};
+		assert_err!(
+			call.validate_self_contained(&source, &extrinsic.get_dispatch_info(), 0)
+				.unwrap(),
+			TransactionValidityError::Invalid(InvalidTransaction::BadSigner)
+		);
+	});
+}
+
 #[test]
 fn transaction_with_invalid_chain_id_should_fail_in_block() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -546,3 +602,30 @@ fn proof_size_base_cost_should_keep_the_same_in_execution_and_estimate() {
 		);
 	});
 }
+
+#[cfg(feature = "evm-tracing")]
+#[test]
+fn transaction_can_be_traced() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let t = legacy_erc20_creation_transaction(alice);
+		let config = fp_evm::TraceConfig {
+			limit: Some(10),
+			..Default::default()
+		};
+		let steps = Ethereum::trace_transaction(t.clone(), &config).expect("transaction is traced");
+		assert_eq!(steps.len(), 10);
+		assert_eq!((steps[0].depth, steps[0].pc, steps[0].opcode), (1, 0, 0x60));
+
+		// The traced transaction is applied, so it cannot be traced again.
+		assert_eq!(
+			pallet_evm::Pallet::<Test>::account_basic(&alice.address)
+				.0
+				.nonce,
+			U256::from(1)
+		);
+		assert!(Ethereum::trace_transaction(t, &config).is_err());
+	});
+}
@@ -38,7 +38,7 @@ frame_support::construct_runtime! {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
-		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>},
+		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>, HoldReason},
 		Utility: pallet_utility::{Pallet, Call, Event},
 	}
 }
@@ -134,6 +134,7 @@ parameter_types! {
 	pub BlockGasLimit: U256 = U256::max_value();
 	pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
 	pub SuicideQuickClearLimit: u32 = 0;
+	pub SuicideIdleClearLimit: u32 = 0;
 }
 impl pallet_evm::Config for Test {
 	type AccountProvider = pallet_evm::FrameSystemAccountProvider<Self>;
@@ -149,6 +150,7 @@ impl pallet_evm::Config for Test {
 	type Currency = Balances;
 
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type PrecompilesType = ();
 	type PrecompilesValue = ();
 	type ChainId = ();
@@ -156,12 +158,23 @@ impl pallet_evm::Config for Test {
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type CreateAddressScheme = ();
 	type FindAuthor = FindAuthorTruncated;
 	type SuicideQuickClearLimit = SuicideQuickClearLimit;
+	type SuicideIdleClearLimit = SuicideIdleClearLimit;
 	type GasLimitPovSizeRatio = ();
 	type GasLimitStorageGrowthRatio = ();
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
+	type MaxCodeSize = ConstU32<{ 24 * 1024 }>;
+	type MaxInitCodeSize = ConstU32<{ 48 * 1024 }>;
+	type MaxRevertOutputLength = ConstU32<256>;
+	type CreateOriginFilter = frame_support::traits::Everything;
+	type CallOriginFilter = frame_support::traits::Everything;
+	type StorageDepositPerSlot = ();
+	type GasQuota = ();
+	type GasQuotaPeriod = ();
+	type PauseOrigin = frame_system::EnsureRoot<Self::AccountId>;
 }
 
 pub(crate) struct MockHandle {
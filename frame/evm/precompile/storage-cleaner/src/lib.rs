@@ -25,10 +25,11 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 use fp_evm::{
 	AccountProvider, PrecompileFailure, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE,
+	STORAGE_DEPOSIT_PROOF_SIZE,
 };
 use pallet_evm::AddressMapping;
 use precompile_utils::{prelude::*, EvmResult};
-use sp_core::H160;
+use sp_core::{Get, H160};
 use sp_runtime::traits::ConstU32;
 
 #[cfg(test)]
@@ -86,16 +87,18 @@ where
 				return Err(revert(alloc::format!("NotSuicided: {}", address)));
 			}
 
-			let deleted = pallet_evm::AccountStorages::<Runtime>::drain_prefix(address)
-				.take((limit.saturating_sub(deleted_entries)) as usize)
-				.count();
+			// The storage deposits of the removed entries are released to their depositors.
+			let (deleted, finished) = pallet_evm::Pallet::<Runtime>::clear_account_storages(
+				address,
+				limit
+					.saturating_sub(deleted_entries)
+					.try_into()
+					.unwrap_or(u32::MAX),
+			);
 			deleted_entries = deleted_entries.saturating_add(deleted as u64);
 
 			// Check if the storage of this contract has been completely removed
-			if pallet_evm::AccountStorages::<Runtime>::iter_key_prefix(address)
-				.next()
-				.is_none()
-			{
+			if finished {
 				Self::clear_suicided_contract(address);
 				deleted_contracts = deleted_contracts.saturating_add(1);
 			}
@@ -119,6 +122,7 @@ where
 	) -> EvmResult {
 		let read_cost = RuntimeHelper::<Runtime>::db_read_gas_cost();
 		let write_cost = RuntimeHelper::<Runtime>::db_write_gas_cost();
+		let deposit_entries = Self::deposit_entries(limit);
 		let ref_time = 0u64
 			// EVM:: Suicided (reads = nb_addresses)
 			.saturating_add(read_cost.saturating_mul(nb_addresses))
@@ -131,7 +135,11 @@ where
 			// EVM: AccountStorage (reads = limit)
 			.saturating_add(read_cost.saturating_mul(limit))
 			// EVM: AccountStorage (writes = limit)
-			.saturating_add(write_cost.saturating_mul(limit));
+			.saturating_add(write_cost.saturating_mul(limit))
+			// EVM: StorageDeposits and System: AccountInfo of the depositors (reads = 2 * limit)
+			.saturating_add(read_cost.saturating_mul(2).saturating_mul(deposit_entries))
+			// EVM: StorageDeposits and System: AccountInfo of the depositors (writes = 2 * limit)
+			.saturating_add(write_cost.saturating_mul(2).saturating_mul(deposit_entries));
 
 		let proof_size = 0u64
 			// Proof: EVM::Suicided (SUICIDED_STORAGE_KEY) * nb_addresses
@@ -139,7 +147,13 @@ where
 			// Proof: EVM::AccountStorage (ACCOUNT_BASIC_PROOF_SIZE) * limit
 			.saturating_add(ACCOUNT_STORAGE_PROOF_SIZE.saturating_mul(limit))
 			// Proof: System::AccountInfo (ACCOUNT_BASIC_PROOF_SIZE) * nb_addresses
-			.saturating_add(ACCOUNT_BASIC_PROOF_SIZE.saturating_mul(nb_addresses));
+			.saturating_add(ACCOUNT_BASIC_PROOF_SIZE.saturating_mul(nb_addresses))
+			// Proof: EVM::StorageDeposits and System::AccountInfo of the depositors * limit
+			.saturating_add(
+				STORAGE_DEPOSIT_PROOF_SIZE
+					.saturating_add(ACCOUNT_BASIC_PROOF_SIZE)
+					.saturating_mul(deposit_entries),
+			);
 
 		handle.record_external_cost(Some(ref_time), Some(proof_size), None)?;
 		Ok(())
@@ -163,14 +177,33 @@ where
 
 		// Refund the cost of the remaining entries
 		if extra_entries > 0 {
+			let extra_deposit_entries = Self::deposit_entries(extra_entries);
 			ref_time = ref_time
 				// EVM:: AccountStorage (reads = extra_entries)
 				.saturating_add(read_cost.saturating_mul(extra_entries))
 				// EVM:: AccountStorage (writes = extra_entries)
-				.saturating_add(write_cost.saturating_mul(extra_entries));
+				.saturating_add(write_cost.saturating_mul(extra_entries))
+				// EVM: StorageDeposits and System: AccountInfo (reads = 2 * extra_entries)
+				.saturating_add(
+					read_cost
+						.saturating_mul(2)
+						.saturating_mul(extra_deposit_entries),
+				)
+				// EVM: StorageDeposits and System: AccountInfo (writes = 2 * extra_entries)
+				.saturating_add(
+					write_cost
+						.saturating_mul(2)
+						.saturating_mul(extra_deposit_entries),
+				);
 			proof_size = proof_size
 				// Proof: EVM::AccountStorage (ACCOUNT_BASIC_PROOF_SIZE) * extra_entries
-				.saturating_add(ACCOUNT_STORAGE_PROOF_SIZE.saturating_mul(extra_entries));
+				.saturating_add(ACCOUNT_STORAGE_PROOF_SIZE.saturating_mul(extra_entries))
+				// Proof: EVM::StorageDeposits and System::AccountInfo * extra_entries
+				.saturating_add(
+					STORAGE_DEPOSIT_PROOF_SIZE
+						.saturating_add(ACCOUNT_BASIC_PROOF_SIZE)
+						.saturating_mul(extra_deposit_entries),
+				);
 		}
 
 		// Refund the cost of the remaining contracts
@@ -194,6 +227,16 @@ where
 		handle.refund_external_cost(Some(ref_time), Some(proof_size));
 	}
 
+	/// Number of the `entries` whose storage deposit release is paid for, none if storage deposits
+	/// are disabled.
+	fn deposit_entries(entries: u64) -> u64 {
+		if Runtime::StorageDepositPerSlot::get().is_zero() {
+			0
+		} else {
+			entries
+		}
+	}
+
 	/// Clears the storage of a suicided contract.
 	///
 	/// This function will remove the given address from the list of suicided contracts
@@ -35,7 +35,7 @@ frame_support::construct_runtime! {
 		System: frame_system::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
-		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>},
+		EVM: pallet_evm::{Pallet, Call, Storage, Config<T>, Event<T>, HoldReason},
 	}
 }
 
@@ -89,7 +89,7 @@ impl pallet_balances::Config for Runtime {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type ReserveIdentifier = ();
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type FreezeIdentifier = ();
 	type MaxLocks = ();
 	type MaxReserves = ();
@@ -123,6 +123,7 @@ parameter_types! {
 	pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
 	pub PrecompilesValue: Precompiles<Runtime> = Precompiles::new();
 	pub SuicideQuickClearLimit: u32 = 0;
+	pub SuicideIdleClearLimit: u32 = 0;
 }
 
 impl pallet_evm::Config for Runtime {
@@ -135,6 +136,7 @@ impl pallet_evm::Config for Runtime {
 	type AddressMapping = IdentityAddressMapping;
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type PrecompilesType = Precompiles<Runtime>;
 	type PrecompilesValue = PrecompilesValue;
@@ -144,11 +146,22 @@ impl pallet_evm::Config for Runtime {
 	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
 	type FindAuthor = ();
 	type OnCreate = ();
+	type CreateAddressScheme = ();
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type GasLimitStorageGrowthRatio = GasLimitStorageGrowthRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
 	type SuicideQuickClearLimit = SuicideQuickClearLimit;
+	type SuicideIdleClearLimit = SuicideIdleClearLimit;
+	type MaxCodeSize = ConstU32<{ 24 * 1024 }>;
+	type MaxInitCodeSize = ConstU32<{ 48 * 1024 }>;
+	type MaxRevertOutputLength = ConstU32<256>;
+	type CreateOriginFilter = frame_support::traits::Everything;
+	type CallOriginFilter = frame_support::traits::Everything;
+	type StorageDepositPerSlot = ();
+	type GasQuota = ();
+	type GasQuotaPeriod = ();
+	type PauseOrigin = frame_system::EnsureRoot<Self::AccountId>;
 }
 
 /// Build test externalities, prepopulated with data for testing the precompile.
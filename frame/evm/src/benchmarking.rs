@@ -15,10 +15,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use super::*;
-use frame_benchmarking::benchmarks;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
 
 type CurrencyOf<T> = <T as Config>::Currency;
 
+/// `JUMPDEST PUSH1 0x01 PUSH1 0x02 ADD POP PUSH1 0x00 JUMP`
+const ARITHMETIC_LOOP: [u8; 10] = [0x5b, 0x60, 0x01, 0x60, 0x02, 0x01, 0x50, 0x60, 0x00, 0x56];
+/// `JUMPDEST PUSH1 0x20 PUSH1 0x00 SHA3 POP PUSH1 0x00 JUMP`
+const KECCAK_LOOP: [u8; 10] = [0x5b, 0x60, 0x20, 0x60, 0x00, 0x20, 0x50, 0x60, 0x00, 0x56];
+/// `JUMPDEST GAS SLOAD POP PUSH1 0x00 JUMP`, reading a different cold slot on each iteration.
+const SLOAD_LOOP: [u8; 7] = [0x5b, 0x5a, 0x54, 0x50, 0x60, 0x00, 0x56];
+/// Emits a log and calls itself with the depth given as calldata decremented, until it is zero.
+///
+/// `PUSH1 0x00 CALLDATALOAD DUP1 ISZERO PUSH1 0x22 JUMPI PUSH1 0x00 PUSH1 0x00 LOG0 PUSH1 0x01
+/// SWAP1 SUB PUSH1 0x00 MSTORE PUSH1 0x00 PUSH1 0x00 PUSH1 0x20 PUSH1 0x00 PUSH1 0x00 ADDRESS GAS
+/// CALL POP JUMPDEST STOP`
+const NESTED_CALL: [u8; 36] = [
+	0x60, 0x00, 0x35, 0x80, 0x15, 0x60, 0x22, 0x57, 0x60, 0x00, 0x60, 0x00, 0xa0, 0x60, 0x01, 0x90,
+	0x03, 0x60, 0x00, 0x52, 0x60, 0x00, 0x60, 0x00, 0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x30, 0x5a,
+	0xf1, 0x50, 0x5b, 0x00,
+];
+
+/// Deploy a contract looping forever over the given code.
+fn deploy_gas_loop<T: Config>(code: &[u8]) -> H160 {
+	let contract = H160::from_low_u64_be(0x1000);
+	Pallet::<T>::create_account(contract, code.to_vec());
+	contract
+}
+
+/// Call the looping contract with a gas limit of `steps` times [`GAS_PER_BENCHMARK_STEP`] on
+/// top of the transaction base cost, running it until it is out of gas.
+fn call_gas_loop<T: Config>(contract: H160, steps: u32) {
+	let info = T::Runner::call(
+		H160::from_low_u64_be(0x2000),
+		contract,
+		Vec::new(),
+		U256::zero(),
+		21_000 + u64::from(steps) * GAS_PER_BENCHMARK_STEP,
+		None,
+		None,
+		None,
+		Vec::new(),
+		false,
+		false,
+		None,
+		None,
+		T::config(),
+	)
+	.map_err(|e| -> sp_runtime::DispatchError { e.error.into() })
+	.expect("call executes");
+	assert_eq!(info.exit_reason, ExitReason::Error(ExitError::OutOfGas));
+}
+
 benchmarks! {
 	withdraw {
 		let caller = frame_benchmarking::whitelisted_caller::<T::AccountId>();
@@ -31,6 +79,128 @@ benchmarks! {
 		assert!(result.is_err());
 		assert_eq!(result.unwrap_err(), sp_runtime::DispatchError::BadOrigin);
 	}
+
+	set_hardfork {
+		<ScheduledHardfork<T>>::put((frame_system::Pallet::<T>::block_number() + 1u32.into(), Hardfork::London));
+	}: _(RawOrigin::Root, Hardfork::London)
+	verify {
+		assert_eq!(Pallet::<T>::hardfork(), Hardfork::London);
+		assert!(<ScheduledHardfork<T>>::get().is_none());
+	}
+
+	schedule_hardfork {
+		let activation = frame_system::Pallet::<T>::block_number() + 1u32.into();
+	}: _(RawOrigin::Root, Hardfork::London, activation)
+	verify {
+		assert_eq!(<ScheduledHardfork<T>>::get(), Some((activation, Hardfork::London)));
+	}
+
+	force_create {
+		let c in 1 .. T::MaxCodeSize::get();
+		let s in 0 .. 100;
+		let address = H160::from_low_u64_be(0x1000);
+		let code = vec![0x00; c as usize];
+		let storage = (0..s)
+			.map(|i| (H256::from_low_u64_be(i.into()), H256::from_low_u64_be(1)))
+			.collect::<Vec<_>>();
+	}: _(RawOrigin::Root, address, code, storage)
+	verify {
+		assert_eq!(<AccountCodes<T>>::decode_len(address), Some(c as usize));
+		assert_eq!(Pallet::<T>::iter_account_storages(&address).count(), s as usize);
+	}
+
+	set_create_allowed {
+		let address = H160::from_low_u64_be(0x1000);
+	}: _(RawOrigin::Root, address, true)
+	verify {
+		assert!(<CreateAllowlist<T>>::contains_key(address));
+	}
+
+	set_call_allowed {
+		let address = H160::from_low_u64_be(0x1000);
+	}: _(RawOrigin::Root, address, true)
+	verify {
+		assert!(<CallAllowlist<T>>::contains_key(address));
+	}
+
+	pause_contract {
+		let origin = T::PauseOrigin::try_successful_origin()
+			.map_err(|_| frame_benchmarking::BenchmarkError::Weightless)?;
+		let address = H160::from_low_u64_be(0x1000);
+	}: _<T::RuntimeOrigin>(origin, address)
+	verify {
+		assert!(<PausedContracts<T>>::contains_key(address));
+	}
+
+	unpause_contract {
+		let origin = T::PauseOrigin::try_successful_origin()
+			.map_err(|_| frame_benchmarking::BenchmarkError::Weightless)?;
+		let address = H160::from_low_u64_be(0x1000);
+		<PausedContracts<T>>::insert(address, ());
+	}: _<T::RuntimeOrigin>(origin, address)
+	verify {
+		assert!(!<PausedContracts<T>>::contains_key(address));
+	}
+
+	set_sponsor_allowance {
+		let sponsor = H160::from_low_u64_be(0x1000);
+		let sender = H160::from_low_u64_be(0x2000);
+		let allowance = U256::from(1_000_000);
+	}: _(RawOrigin::Root, sponsor, sender, allowance)
+	verify {
+		assert_eq!(<SponsorAllowances<T>>::get(sponsor, sender), allowance);
+	}
+
+	evm_gas_arithmetic {
+		let g in 1 .. 1_000;
+		let contract = deploy_gas_loop::<T>(&ARITHMETIC_LOOP);
+	}: {
+		call_gas_loop::<T>(contract, g);
+	}
+
+	evm_gas_keccak {
+		let g in 1 .. 1_000;
+		let contract = deploy_gas_loop::<T>(&KECCAK_LOOP);
+	}: {
+		call_gas_loop::<T>(contract, g);
+	}
+
+	evm_gas_sload {
+		let g in 1 .. 1_000;
+		let contract = deploy_gas_loop::<T>(&SLOAD_LOOP);
+	}: {
+		call_gas_loop::<T>(contract, g);
+	}
+
+	// Call frames `d` deep, each emitting a log, to measure the cost of entering and exiting the
+	// substates of nested calls.
+	evm_nested_calls {
+		let d in 1 .. 256;
+		let contract = H160::from_low_u64_be(0x1000);
+		Pallet::<T>::create_account(contract, NESTED_CALL.to_vec());
+		let input = H256::from_low_u64_be(d.into()).as_bytes().to_vec();
+	}: {
+		let info = T::Runner::call(
+			H160::from_low_u64_be(0x2000),
+			contract,
+			input,
+			U256::zero(),
+			10_000_000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false,
+			false,
+			None,
+			None,
+			T::config(),
+		)
+		.map_err(|e| -> sp_runtime::DispatchError { e.error.into() })
+		.expect("call executes");
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(info.logs.len(), d as usize);
+	}
 }
 
-// impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::mock::Test);
+impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::mock::Test);
@@ -48,7 +48,9 @@
 //! the exact same transaction / receipt format. However, given one Ethereum transaction and one Substrate account's
 //! private key, one should be able to convert any Ethereum transaction into a transaction compatible with this pallet.
 //!
-//! The gas configurations are configurable. Right now, a pre-defined London hard fork configuration option is provided.
+//! The gas configurations are configurable. Pre-defined London ([`LONDON_CONFIG`]) and Shanghai ([`SHANGHAI_CONFIG`])
+//! hard fork configurations are provided, and a runtime selects one by overriding [`Config::config`]. A runtime can also
+//! follow the [`Hardfork`] stored on-chain, which can be switched, or scheduled at a block height, by root.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -60,17 +62,21 @@ extern crate alloc;
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 pub mod runner;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "evm-tracing")]
+pub mod tracing;
 pub mod weights;
 
-use alloc::{collections::btree_map::BTreeMap, vec::Vec};
+use alloc::{borrow::Cow, collections::btree_map::BTreeMap, vec::Vec};
 use core::cmp::min;
 pub use evm::{
-	Config as EvmConfig, Context, ExitError, ExitFatal, ExitReason, ExitRevert, ExitSucceed,
+	Config as EvmConfig, Context, CreateScheme, ExitError, ExitFatal, ExitReason, ExitRevert,
+	ExitSucceed,
 };
 use hash_db::Hasher;
 use impl_trait_for_tuples::impl_for_tuples;
@@ -79,33 +85,37 @@ use scale_info::TypeInfo;
 // Substrate
 use frame_support::{
 	dispatch::{DispatchResultWithPostInfo, Pays, PostDispatchInfo},
-	storage::{child::KillStorageResult, KeyPrefixIterator},
+	storage::KeyPrefixIterator,
 	traits::{
-		fungible::{Balanced, Credit, Debt},
+		fungible::{Balanced, Credit, Debt, MutateHold},
+		fungibles,
 		tokens::{
 			currency::Currency,
 			fungible::Inspect,
 			imbalance::{Imbalance, OnUnbalanced, SignedImbalance},
 			ExistenceRequirement, Fortitude, Precision, Preservation, WithdrawReasons,
 		},
-		FindAuthor, Get, Time,
+		Contains, FindAuthor, Get, Time,
 	},
-	weights::Weight,
+	weights::{Weight, WeightMeter},
 };
 use frame_system::RawOrigin;
 use sp_core::{H160, H256, U256};
 use sp_runtime::{
 	traits::{BadOrigin, NumberFor, Saturating, UniqueSaturatedInto, Zero},
-	AccountId32, DispatchErrorWithPostInfo,
+	AccountId32, DispatchErrorWithPostInfo, Permill,
 };
 // Frontier
 use fp_account::AccountId20;
-use fp_evm::GenesisAccount;
 pub use fp_evm::{
-	Account, AccountProvider, CallInfo, CreateInfo, ExecutionInfoV2 as ExecutionInfo,
-	FeeCalculator, IsPrecompileResult, LinearCostPrecompile, Log, Precompile, PrecompileFailure,
-	PrecompileHandle, PrecompileOutput, PrecompileResult, PrecompileSet,
-	TransactionValidationError, Vicinity,
+	Account, AccountProvider, BlockUtilization, CallInfo, CreateInfo,
+	ExecutionInfoV2 as ExecutionInfo, FeeCalculator, IsPrecompileResult, LinearCostPrecompile, Log,
+	Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult,
+	PrecompileSet, StateOverride, TransactionValidationError, Vicinity,
+};
+use fp_evm::{
+	GenesisAccount, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE,
+	GAS_QUOTA_PERIOD_PROOF_SIZE, GAS_QUOTA_USAGE_PROOF_SIZE, STORAGE_DEPOSIT_PROOF_SIZE,
 };
 
 pub use self::{
@@ -120,7 +130,11 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The in-code storage version, see [`crate::migrations`].
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -155,14 +169,20 @@ pub mod pallet {
 		#[pallet::no_default_bounds]
 		type AddressMapping: AddressMapping<AccountIdOf<Self>>;
 
-		/// Currency type for withdraw and balance storage.
+		/// Currency type for withdraw and balance storage, also holding storage deposits.
 		#[pallet::no_default]
-		type Currency: Currency<AccountIdOf<Self>> + Inspect<AccountIdOf<Self>>;
+		type Currency: Currency<AccountIdOf<Self>>
+			+ Inspect<AccountIdOf<Self>>
+			+ MutateHold<AccountIdOf<Self>, Reason = Self::RuntimeHoldReason>;
 
 		/// The overarching event type.
 		#[pallet::no_default_bounds]
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// The overarching hold reason.
+		#[pallet::no_default_bounds]
+		type RuntimeHoldReason: From<HoldReason>;
+
 		/// Precompiles associated with this EVM engine.
 		type PrecompilesType: PrecompileSet;
 		type PrecompilesValue: Get<Self::PrecompilesType>;
@@ -186,6 +206,11 @@ pub mod pallet {
 		#[pallet::no_default_bounds]
 		type OnCreate: OnCreate<Self>;
 
+		/// Derivation of the address of contracts created at the top level. `()` keeps the
+		/// standard `CREATE`/`CREATE2` derivation, see [`NamespacedDeployer`] for system contracts.
+		#[pallet::no_default_bounds]
+		type CreateAddressScheme: CreateAddressScheme<Self>;
+
 		/// Find author for the current block.
 		type FindAuthor: FindAuthor<H160>;
 
@@ -195,9 +220,57 @@ pub mod pallet {
 		/// Define the quick clear limit of storage clearing when a contract suicides. Set to 0 to disable it.
 		type SuicideQuickClearLimit: Get<u32>;
 
+		/// Define the maximum number of storage entries of suicided contracts removed in `on_idle`
+		/// per block. Set to 0 to disable it.
+		type SuicideIdleClearLimit: Get<u32>;
+
 		/// Gas limit storage growth ratio.
 		type GasLimitStorageGrowthRatio: Get<u64>;
 
+		/// Maximum size in bytes of the code deployed by a contract creation (EIP-170).
+		///
+		/// Creations exceeding it inside the EVM fail with `ExitError::CreateContractLimit`, the
+		/// deployed code only being known once the init code ran. `force_create` fails with
+		/// [`Error::MaxCodeSizeExceeded`].
+		#[pallet::constant]
+		type MaxCodeSize: Get<u32>;
+
+		/// Maximum size in bytes of contract creation init code (EIP-3860). Only enforced when the
+		/// active EVM configuration limits init code.
+		#[pallet::constant]
+		type MaxInitCodeSize: Get<u32>;
+
+		/// Maximum size in bytes of the revert output included in [`Event::ExecutedFailed`].
+		#[pallet::constant]
+		type MaxRevertOutputLength: Get<u32>;
+
+		/// Addresses allowed to create contracts at the top level, e.g. [`AllowedCreators`] for
+		/// permissioned deployments. Only transactions are filtered, not `eth_call` or
+		/// `eth_estimateGas`.
+		type CreateOriginFilter: Contains<H160>;
+
+		/// Addresses allowed to call at the top level, e.g. [`AllowedCallers`]. This applies to
+		/// every top-level call transaction, including plain value transfers and calls into
+		/// precompiles.
+		type CallOriginFilter: Contains<H160>;
+
+		/// Deposit held from the transaction origin for each storage slot created by a contract,
+		/// released to it when the slot is cleared. Set to 0 to disable it.
+		type StorageDepositPerSlot: Get<U256>;
+
+		/// Gas each sender may use in transactional calls per [`Config::GasQuotaPeriod`],
+		/// refilled at the start of every period. Set to 0 to disable it.
+		#[pallet::constant]
+		type GasQuota: Get<u64>;
+
+		/// Length in blocks of the gas quota periods. Set to 0 to disable gas quotas.
+		#[pallet::constant]
+		type GasQuotaPeriod: Get<u32>;
+
+		/// Origin allowed to pause and unpause contracts.
+		#[pallet::no_default_bounds]
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
 		/// Get the timestamp for the current block.
 		#[pallet::no_default]
 		type Timestamp: Time;
@@ -206,6 +279,10 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 
 		/// EVM config used in the module.
+		///
+		/// Runtimes following the hard fork activated on-chain, see [`Pallet::hardfork`], return
+		/// `Pallet::<Self>::hardfork().config()`. This reads [`ChainConfig`] on every call, which
+		/// is only accounted for once per block in `on_initialize`.
 		fn config() -> &'static EvmConfig {
 			&SHANGHAI_CONFIG
 		}
@@ -214,7 +291,7 @@ pub mod pallet {
 	pub mod config_preludes {
 		use super::*;
 		use core::str::FromStr;
-		use frame_support::{derive_impl, parameter_types, ConsensusEngineId};
+		use frame_support::{derive_impl, parameter_types, traits::Everything, ConsensusEngineId};
 		use sp_runtime::traits::BlakeTwo256;
 
 		pub struct TestDefaultConfig;
@@ -229,6 +306,8 @@ pub mod pallet {
 		const MAX_POV_SIZE: u64 = 5 * 1024 * 1024;
 		/// The maximum storage growth per block in bytes.
 		const MAX_STORAGE_GROWTH: u64 = 400 * 1024;
+		/// The maximum deployed code size in bytes, as defined by EIP-170.
+		const MAX_CODE_SIZE: u32 = 24 * 1024;
 
 		parameter_types! {
 			pub BlockGasLimit: U256 = U256::from(BLOCK_GAS_LIMIT);
@@ -237,6 +316,10 @@ pub mod pallet {
 			pub const GasLimitStorageGrowthRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_STORAGE_GROWTH);
 			pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
 			pub SuicideQuickClearLimit: u32 = 0;
+			pub SuicideIdleClearLimit: u32 = 0;
+			pub const MaxCodeSize: u32 = MAX_CODE_SIZE;
+			pub const MaxInitCodeSize: u32 = 2 * MAX_CODE_SIZE;
+			pub const MaxRevertOutputLength: u32 = 256;
 		}
 
 		#[register_default_impl(TestDefaultConfig)]
@@ -249,16 +332,29 @@ pub mod pallet {
 			type WeightPerGas = WeightPerGas;
 			#[inject_runtime_type]
 			type RuntimeEvent = ();
+			#[inject_runtime_type]
+			type RuntimeHoldReason = ();
 			type PrecompilesType = ();
 			type PrecompilesValue = ();
 			type ChainId = ChainId;
 			type BlockGasLimit = BlockGasLimit;
 			type OnChargeTransaction = ();
 			type OnCreate = ();
+			type CreateAddressScheme = ();
 			type FindAuthor = FindAuthorTruncated;
 			type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 			type GasLimitStorageGrowthRatio = GasLimitStorageGrowthRatio;
 			type SuicideQuickClearLimit = SuicideQuickClearLimit;
+			type SuicideIdleClearLimit = SuicideIdleClearLimit;
+			type MaxCodeSize = MaxCodeSize;
+			type MaxInitCodeSize = MaxInitCodeSize;
+			type MaxRevertOutputLength = MaxRevertOutputLength;
+			type CreateOriginFilter = Everything;
+			type CallOriginFilter = Everything;
+			type StorageDepositPerSlot = ();
+			type GasQuota = ();
+			type GasQuotaPeriod = ();
+			type PauseOrigin = frame_system::EnsureRoot<Self::AccountId>;
 			type WeightInfo = ();
 		}
 
@@ -286,6 +382,35 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let weight = match <ScheduledHardfork<T>>::get() {
+				Some((activation, hardfork)) if activation <= n => {
+					<ScheduledHardfork<T>>::kill();
+					<ChainConfig<T>>::put(hardfork);
+					Self::deposit_event(Event::<T>::HardforkActivated { hardfork });
+					T::DbWeight::get().reads_writes(1, 2)
+				}
+				_ => T::DbWeight::get().reads(1),
+			};
+
+			// Load the active hard fork once per block, the reads of runtimes following it in
+			// `Config::config` during the block are then served from the storage cache and not
+			// metered again.
+			let _ = Self::hardfork();
+			<BlockGasUsed<T>>::kill();
+			weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		}
+
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let weight = Self::clear_suicided_storage(remaining_weight);
+			weight.saturating_add(Self::clear_gas_quota_usage(
+				remaining_weight.saturating_sub(weight),
+			))
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Withdraw balance from EVM into currency/balances pallet.
@@ -359,14 +484,7 @@ pub mod pallet {
 				}
 			};
 
-			match info.exit_reason {
-				ExitReason::Succeed(_) => {
-					Pallet::<T>::deposit_event(Event::<T>::Executed { address: target });
-				}
-				_ => {
-					Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed { address: target });
-				}
-			};
+			Pallet::<T>::deposit_executed_event(target, &info.exit_reason, info.value);
 
 			Ok(PostDispatchInfo {
 				actual_weight: {
@@ -559,6 +677,264 @@ pub mod pallet {
 				pays_fee: Pays::No,
 			})
 		}
+
+		/// Activate the given EVM hard fork immediately, discarding any scheduled activation.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_hardfork())]
+		pub fn set_hardfork(origin: OriginFor<T>, hardfork: Hardfork) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<ScheduledHardfork<T>>::kill();
+			<ChainConfig<T>>::put(hardfork);
+			Self::deposit_event(Event::<T>::HardforkActivated { hardfork });
+
+			Ok(())
+		}
+
+		/// Schedule the given EVM hard fork to be activated at the beginning of the block
+		/// `activation`, replacing any previously scheduled activation.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::schedule_hardfork())]
+		pub fn schedule_hardfork(
+			origin: OriginFor<T>,
+			hardfork: Hardfork,
+			activation: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				activation > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::HardforkActivationInPast
+			);
+
+			<ScheduledHardfork<T>>::put((activation, hardfork));
+			Self::deposit_event(Event::<T>::HardforkScheduled {
+				hardfork,
+				activation,
+			});
+
+			Ok(())
+		}
+
+		/// Install the given code and storage at `address`, e.g. to deploy a system contract at a
+		/// canonical address.
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::force_create(
+			code.len() as u32,
+			storage.len() as u32,
+		))]
+		pub fn force_create(
+			origin: OriginFor<T>,
+			address: H160,
+			code: Vec<u8>,
+			storage: Vec<(H256, H256)>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!code.is_empty(), Error::<T>::InvalidContractCode);
+			ensure!(
+				code.len() <= T::MaxCodeSize::get() as usize,
+				Error::<T>::MaxCodeSizeExceeded
+			);
+			ensure!(
+				!<AccountCodes<T>>::contains_key(address) && !<Suicided<T>>::contains_key(address),
+				Error::<T>::ContractAlreadyExists
+			);
+			ensure!(
+				!matches!(
+					T::PrecompilesValue::get().is_precompile(address, u64::MAX),
+					IsPrecompileResult::Answer {
+						is_precompile: true,
+						..
+					}
+				),
+				Error::<T>::PrecompileAddress
+			);
+
+			Self::create_account(address, code);
+			// Contract accounts start with a nonce of one (EIP-161).
+			let account_id = T::AddressMapping::into_account_id(address);
+			if T::AccountProvider::account_nonce(&account_id).is_zero() {
+				T::AccountProvider::inc_account_nonce(&account_id);
+			}
+			for (index, value) in storage {
+				if !value.is_zero() {
+					<AccountStorages<T>>::insert(address, index, value);
+				}
+			}
+			Self::deposit_event(Event::<T>::Created { address });
+
+			Ok(())
+		}
+
+		/// Allow or disallow `address` to create contracts when using [`AllowedCreators`].
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_create_allowed())]
+		pub fn set_create_allowed(
+			origin: OriginFor<T>,
+			address: H160,
+			allowed: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			if allowed {
+				<CreateAllowlist<T>>::insert(address, ());
+			} else {
+				<CreateAllowlist<T>>::remove(address);
+			}
+			Self::deposit_event(Event::<T>::CreateAllowlistUpdated { address, allowed });
+
+			Ok(())
+		}
+
+		/// Allow or disallow `address` to call, including value transfers, when using
+		/// [`AllowedCallers`].
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_call_allowed())]
+		pub fn set_call_allowed(
+			origin: OriginFor<T>,
+			address: H160,
+			allowed: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			if allowed {
+				<CallAllowlist<T>>::insert(address, ());
+			} else {
+				<CallAllowlist<T>>::remove(address);
+			}
+			Self::deposit_event(Event::<T>::CallAllowlistUpdated { address, allowed });
+
+			Ok(())
+		}
+
+		/// Pause the contract at `address`, making any call into it revert.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::pause_contract())]
+		pub fn pause_contract(origin: OriginFor<T>, address: H160) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			<PausedContracts<T>>::insert(address, ());
+			Self::deposit_event(Event::<T>::ContractPaused { address });
+
+			Ok(())
+		}
+
+		/// Unpause the contract at `address`.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::unpause_contract())]
+		pub fn unpause_contract(origin: OriginFor<T>, address: H160) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			<PausedContracts<T>>::remove(address);
+			Self::deposit_event(Event::<T>::ContractUnpaused { address });
+
+			Ok(())
+		}
+
+		/// Set the fee allowance of `sponsor` for calls from `sender`, see
+		/// [`Pallet::sponsored_call`].
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_sponsor_allowance())]
+		pub fn set_sponsor_allowance(
+			origin: OriginFor<T>,
+			sponsor: H160,
+			sender: H160,
+			allowance: U256,
+		) -> DispatchResult {
+			T::CallOrigin::ensure_address_origin(&sponsor, origin)?;
+
+			if allowance.is_zero() {
+				<SponsorAllowances<T>>::remove(sponsor, sender);
+			} else {
+				<SponsorAllowances<T>>::insert(sponsor, sender, allowance);
+			}
+			Self::deposit_event(Event::<T>::SponsorAllowanceSet {
+				sponsor,
+				sender,
+				allowance,
+			});
+
+			Ok(())
+		}
+
+		/// Issue an EVM call operation from `source` whose fee is paid by `sponsor`, out of the
+		/// allowance set with [`Pallet::set_sponsor_allowance`].
+		#[pallet::call_index(12)]
+		#[pallet::weight({
+			let without_base_extrinsic_weight = true;
+			T::GasWeightMapping::gas_to_weight(*gas_limit, without_base_extrinsic_weight)
+				.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+		})]
+		pub fn sponsored_call(
+			origin: OriginFor<T>,
+			source: H160,
+			sponsor: H160,
+			target: H160,
+			input: Vec<u8>,
+			value: U256,
+			gas_limit: u64,
+			max_fee_per_gas: U256,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Vec<(H160, Vec<H256>)>,
+		) -> DispatchResultWithPostInfo {
+			T::CallOrigin::ensure_address_origin(&source, origin)?;
+
+			let is_transactional = true;
+			let validate = true;
+			let info = match T::Runner::call_sponsored(
+				sponsor,
+				source,
+				target,
+				input,
+				value,
+				gas_limit,
+				Some(max_fee_per_gas),
+				max_priority_fee_per_gas,
+				nonce,
+				access_list,
+				is_transactional,
+				validate,
+				None,
+				None,
+				T::config(),
+			) {
+				Ok(info) => info,
+				Err(e) => {
+					return Err(DispatchErrorWithPostInfo {
+						post_info: PostDispatchInfo {
+							actual_weight: Some(e.weight),
+							pays_fee: Pays::Yes,
+						},
+						error: e.error.into(),
+					})
+				}
+			};
+
+			Pallet::<T>::deposit_executed_event(target, &info.exit_reason, info.value);
+
+			Ok(PostDispatchInfo {
+				actual_weight: {
+					let mut gas_to_weight = T::GasWeightMapping::gas_to_weight(
+						info.used_gas.standard.unique_saturated_into(),
+						true,
+					);
+					if let Some(weight_info) = info.weight_info {
+						if let Some(proof_size_usage) = weight_info.proof_size_usage {
+							*gas_to_weight.proof_size_mut() = proof_size_usage;
+						}
+					}
+					Some(gas_to_weight)
+				},
+				pays_fee: Pays::No,
+			})
+		}
+	}
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Deposit of a contract storage slot, see [`Config::StorageDepositPerSlot`].
+		StorageDeposit,
 	}
 
 	#[pallet::event]
@@ -573,7 +949,36 @@ pub mod pallet {
 		/// A contract has been executed successfully with states applied.
 		Executed { address: H160 },
 		/// A contract has been executed with errors. States are reverted with only gas fees applied.
-		ExecutedFailed { address: H160 },
+		/// `output` holds the revert data, truncated to [`Config::MaxRevertOutputLength`].
+		ExecutedFailed { address: H160, output: Vec<u8> },
+		/// An EVM hard fork has been activated.
+		HardforkActivated { hardfork: Hardfork },
+		/// An EVM hard fork has been scheduled for activation.
+		HardforkScheduled {
+			hardfork: Hardfork,
+			activation: BlockNumberFor<T>,
+		},
+		/// Storage entries of a suicided contract have been removed. `finished` is set once all of
+		/// its storage has been cleared and the account has been removed.
+		SuicidedStorageCleared {
+			address: H160,
+			entries: u32,
+			finished: bool,
+		},
+		/// An address has been added to or removed from the contract creation allowlist.
+		CreateAllowlistUpdated { address: H160, allowed: bool },
+		/// An address has been added to or removed from the call allowlist.
+		CallAllowlistUpdated { address: H160, allowed: bool },
+		/// A contract has been paused.
+		ContractPaused { address: H160 },
+		/// A contract has been unpaused.
+		ContractUnpaused { address: H160 },
+		/// The fee allowance of `sponsor` for calls from `sender` has been set.
+		SponsorAllowanceSet {
+			sponsor: H160,
+			sender: H160,
+			allowance: U256,
+		},
 	}
 
 	#[pallet::error]
@@ -602,6 +1007,28 @@ pub mod pallet {
 		Reentrancy,
 		/// EIP-3607,
 		TransactionMustComeFromEOA,
+		/// EIP-3860, init code exceeds the maximum size.
+		MaxInitCodeSizeExceeded,
+		/// Hard fork activation block is not in the future.
+		HardforkActivationInPast,
+		/// Contract code is empty.
+		InvalidContractCode,
+		/// A contract already exists at the given address.
+		ContractAlreadyExists,
+		/// The origin is not allowed to create contracts.
+		CreateOriginNotAllowed,
+		/// The origin is not allowed to call.
+		CallOriginNotAllowed,
+		/// The sponsor allowance does not cover the transaction fee.
+		SponsorAllowanceTooLow,
+		/// The gas limit exceeds the remaining gas quota of the sender.
+		GasQuotaExceeded,
+		/// The address is used by a precompile.
+		PrecompileAddress,
+		/// The runner does not support sponsored calls.
+		SponsoredCallUnsupported,
+		/// EIP-170, contract code exceeds the maximum size.
+		MaxCodeSizeExceeded,
 		/// Undefined error.
 		Undefined,
 	}
@@ -619,6 +1046,17 @@ pub mod pallet {
 				TransactionValidationError::InvalidFeeInput => Error::<T>::GasPriceTooLow,
 				TransactionValidationError::InvalidChainId => Error::<T>::InvalidChainId,
 				TransactionValidationError::InvalidSignature => Error::<T>::InvalidSignature,
+				TransactionValidationError::SenderHasCode => Error::<T>::TransactionMustComeFromEOA,
+				TransactionValidationError::MaxInitCodeSizeExceeded => {
+					Error::<T>::MaxInitCodeSizeExceeded
+				}
+				TransactionValidationError::GasQuotaExceeded => Error::<T>::GasQuotaExceeded,
+				TransactionValidationError::CreateOriginNotAllowed => {
+					Error::<T>::CreateOriginNotAllowed
+				}
+				TransactionValidationError::CallOriginNotAllowed => {
+					Error::<T>::CallOriginNotAllowed
+				}
 				TransactionValidationError::UnknownError => Error::<T>::Undefined,
 			}
 		}
@@ -673,12 +1111,84 @@ pub mod pallet {
 	pub type AccountCodesMetadata<T: Config> =
 		StorageMap<_, Blake2_128Concat, H160, CodeMetadata, OptionQuery>;
 
+	/// Contract code by code hash, stored once for all the contracts sharing it, in addition to
+	/// their [`AccountCodes`]. Filled for the contracts deployed before it by
+	/// [`migrations::CodesByHashMigration`](crate::migrations::CodesByHashMigration).
+	#[pallet::storage]
+	pub type CodesByHash<T: Config> = StorageMap<_, Identity, H256, Vec<u8>, OptionQuery>;
+
+	/// Number of contracts whose code is stored in [`CodesByHash`], i.e. having
+	/// [`AccountCodesMetadata`].
+	#[pallet::storage]
+	pub type CodeReferences<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
+
 	#[pallet::storage]
 	pub type AccountStorages<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, H160, Blake2_128Concat, H256, H256, ValueQuery>;
 
 	#[pallet::storage]
 	pub type Suicided<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+	/// The active EVM hard fork. [`Hardfork::default`] is used when unset.
+	///
+	/// Read once per block in `on_initialize`, hence whitelisted.
+	#[pallet::storage]
+	#[pallet::whitelist_storage]
+	pub type ChainConfig<T: Config> = StorageValue<_, Hardfork, OptionQuery>;
+
+	/// Gas used by the EVM executions of the current block.
+	///
+	/// Updated by every execution like `System::BlockWeight`, hence whitelisted.
+	#[pallet::storage]
+	#[pallet::whitelist_storage]
+	pub type BlockGasUsed<T: Config> = StorageValue<_, U256, ValueQuery>;
+
+	/// The EVM hard fork scheduled for activation, with its activation block.
+	#[pallet::storage]
+	pub type ScheduledHardfork<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, Hardfork), OptionQuery>;
+
+	/// Addresses allowed to create contracts by [`AllowedCreators`].
+	#[pallet::storage]
+	pub type CreateAllowlist<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+	/// Addresses allowed to call by [`AllowedCallers`].
+	#[pallet::storage]
+	pub type CallAllowlist<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+	/// Storage deposits held for the storage slots of contracts, with the account holding them.
+	#[pallet::storage]
+	pub type StorageDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		H160,
+		Blake2_128Concat,
+		H256,
+		(AccountIdOf<T>, DepositBalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// Paused contracts, calls into them revert with [`runner::stack::contract_paused_output`].
+	#[pallet::storage]
+	pub type PausedContracts<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+	/// Remaining fee allowance of a sponsor for calls from a sender, see
+	/// [`Pallet::sponsored_call`].
+	#[pallet::storage]
+	pub type SponsorAllowances<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, H160, Blake2_128Concat, H160, U256, ValueQuery>;
+
+	/// Gas used by each sender in a gas quota period, by index of the period.
+	///
+	/// The entries of the past periods are removed in `on_idle`, see
+	/// [`Pallet::clear_gas_quota_usage`].
+	#[pallet::storage]
+	pub type GasQuotaUsage<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u32, Blake2_128Concat, H160, u64, ValueQuery>;
+
+	/// Indexes of the gas quota periods with entries in [`GasQuotaUsage`].
+	#[pallet::storage]
+	pub type GasQuotaPeriods<T: Config> = StorageMap<_, Twox64Concat, u32, (), OptionQuery>;
 }
 
 /// Utility alias for easy access to the [`AccountProvider::AccountId`] type from a given config.
@@ -687,6 +1197,9 @@ pub type AccountIdOf<T> = <<T as Config>::AccountProvider as AccountProvider>::A
 /// Type alias for currency balance.
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
 
+/// Type alias for the balance of the storage deposits held by [`Config::Currency`].
+pub type DepositBalanceOf<T> = <<T as Config>::Currency as Inspect<AccountIdOf<T>>>::Balance;
+
 /// Type alias for negative imbalance during fees
 type NegativeImbalanceOf<C, T> = <C as Currency<AccountIdOf<T>>>::NegativeImbalance;
 
@@ -715,6 +1228,35 @@ impl CodeMetadata {
 	}
 }
 
+/// EVM hard fork selecting the configuration used for execution.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	Default,
+	Eq,
+	PartialEq,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen
+)]
+pub enum Hardfork {
+	London,
+	#[default]
+	Shanghai,
+}
+
+impl Hardfork {
+	/// Get the EVM configuration of the hard fork.
+	pub fn config(&self) -> &'static EvmConfig {
+		match self {
+			Hardfork::London => &LONDON_CONFIG,
+			Hardfork::Shanghai => &SHANGHAI_CONFIG,
+		}
+	}
+}
+
 pub trait EnsureAddressOrigin<OuterOrigin> {
 	/// Success return type.
 	type Success;
@@ -906,9 +1448,78 @@ where
 	}
 }
 
-static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
+/// Amount of gas consumed per step of the `evm_gas_*` benchmarks.
+///
+/// Dividing the per-step weight of the most expensive of these benchmarks by this amount gives
+/// the ref time and proof size per gas to configure as [`Config::WeightPerGas`] and
+/// [`Config::GasLimitPovSizeRatio`].
+pub const GAS_PER_BENCHMARK_STEP: u64 = 1_000;
+
+/// [`Contains`] filter of the addresses in [`CreateAllowlist`].
+pub struct AllowedCreators<T>(core::marker::PhantomData<T>);
+impl<T: Config> Contains<H160> for AllowedCreators<T> {
+	fn contains(address: &H160) -> bool {
+		<CreateAllowlist<T>>::contains_key(address)
+	}
+}
+
+/// [`Contains`] filter of the addresses in [`CallAllowlist`].
+pub struct AllowedCallers<T>(core::marker::PhantomData<T>);
+impl<T: Config> Contains<H160> for AllowedCallers<T> {
+	fn contains(address: &H160) -> bool {
+		<CallAllowlist<T>>::contains_key(address)
+	}
+}
+
+/// EVM configuration of the London hard fork.
+pub static LONDON_CONFIG: EvmConfig = EvmConfig::london();
+
+/// EVM configuration of the Shanghai hard fork, enabling the `PUSH0` opcode (EIP-3855) and
+/// limiting and metering init code (EIP-3860).
+pub static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
 
 impl<T: Config> Pallet<T> {
+	/// Get the active EVM hard fork.
+	pub fn hardfork() -> Hardfork {
+		<ChainConfig<T>>::get().unwrap_or_default()
+	}
+
+	/// Get the given EVM configuration with the [`Config::MaxCodeSize`] and
+	/// [`Config::MaxInitCodeSize`] limits applied, only cloned if it has different limits.
+	pub fn config_with_code_limits(config: &EvmConfig) -> Cow<'_, EvmConfig> {
+		let create_contract_limit = Some(T::MaxCodeSize::get() as usize);
+		let max_initcode_size = config
+			.max_initcode_size
+			.map(|_| T::MaxInitCodeSize::get() as usize);
+		if config.create_contract_limit == create_contract_limit
+			&& config.max_initcode_size == max_initcode_size
+		{
+			return Cow::Borrowed(config);
+		}
+
+		let mut config = config.clone();
+		config.create_contract_limit = create_contract_limit;
+		config.max_initcode_size = max_initcode_size;
+		Cow::Owned(config)
+	}
+
+	/// Ensure `source` passes [`Config::CreateOriginFilter`] when `target` is `None`, or
+	/// [`Config::CallOriginFilter`] otherwise.
+	pub fn ensure_origin_allowed(
+		source: &H160,
+		target: Option<H160>,
+	) -> Result<(), TransactionValidationError> {
+		match target {
+			None if !T::CreateOriginFilter::contains(source) => {
+				Err(TransactionValidationError::CreateOriginNotAllowed)
+			}
+			Some(_) if !T::CallOriginFilter::contains(source) => {
+				Err(TransactionValidationError::CallOriginNotAllowed)
+			}
+			_ => Ok(()),
+		}
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let (account, _) = Self::account_basic(address);
@@ -945,22 +1556,140 @@ impl<T: Config> Pallet<T> {
 		}
 
 		<AccountCodes<T>>::remove(address);
-		<AccountCodesMetadata<T>>::remove(address);
+		if let Some(meta) = <AccountCodesMetadata<T>>::take(address) {
+			Self::release_code(meta.hash);
+		}
 
 		if T::SuicideQuickClearLimit::get() > 0 {
-			#[allow(deprecated)]
-			let res = <AccountStorages<T>>::remove_prefix(address, Some(T::SuicideQuickClearLimit::get()));
+			let (_, finished) =
+				Self::clear_account_storages(*address, T::SuicideQuickClearLimit::get());
+			if finished {
+				<Suicided<T>>::remove(address);
 
-			match res {
-				KillStorageResult::AllRemoved(_) => {
-					<Suicided<T>>::remove(address);
+				let account_id = T::AddressMapping::into_account_id(*address);
+				T::AccountProvider::remove_account(&account_id);
+			}
+		}
+	}
 
-					let account_id = T::AddressMapping::into_account_id(*address);
-					T::AccountProvider::remove_account(&account_id);
-				}
-				KillStorageResult::SomeRemaining(_) => (),
+	/// Remove at most `limit` storage entries of `address`, releasing their storage deposits.
+	/// Returns the number of removed entries and whether all the entries are removed.
+	pub fn clear_account_storages(address: H160, limit: u32) -> (u32, bool) {
+		let indexes: Vec<H256> = <AccountStorages<T>>::iter_key_prefix(address)
+			.take(limit as usize)
+			.collect();
+		for index in &indexes {
+			<AccountStorages<T>>::remove(address, index);
+			Self::release_storage_deposit(address, *index);
+		}
+		let finished = <AccountStorages<T>>::iter_key_prefix(address)
+			.next()
+			.is_none();
+
+		(indexes.len() as u32, finished)
+	}
+
+	/// Release the storage deposit of the storage slot `index` of `address` to the account
+	/// holding it.
+	pub fn release_storage_deposit(address: H160, index: H256) {
+		// Slots predating the deposit have nothing to release.
+		if let Some((depositor, deposit)) = <StorageDeposits<T>>::take(address, index) {
+			let _ = T::Currency::release(
+				&T::RuntimeHoldReason::from(HoldReason::StorageDeposit),
+				&depositor,
+				deposit,
+				Precision::BestEffort,
+			);
+		}
+	}
+
+	/// Weight of [`Self::release_storage_deposit`], zero if storage deposits are disabled.
+	pub fn storage_deposit_release_weight() -> Weight {
+		if T::StorageDepositPerSlot::get().is_zero() {
+			return Weight::zero();
+		}
+		// The `StorageDeposits` entry and the balance of the depositor are read and written.
+		T::DbWeight::get()
+			.reads_writes(2, 2)
+			.saturating_add(Weight::from_parts(
+				0,
+				STORAGE_DEPOSIT_PROOF_SIZE.saturating_add(ACCOUNT_BASIC_PROOF_SIZE),
+			))
+	}
+
+	/// Clear the remaining storage of suicided contracts, removing at most
+	/// [`Config::SuicideIdleClearLimit`] entries within the given weight limit. Returns the
+	/// consumed weight.
+	pub fn clear_suicided_storage(weight_limit: Weight) -> Weight {
+		let limit = T::SuicideIdleClearLimit::get();
+		if limit == 0 {
+			return Weight::zero();
+		}
+
+		let db_weight = T::DbWeight::get();
+		// Removing a single storage entry and releasing its storage deposit.
+		let entry_weight = db_weight
+			.reads_writes(1, 1)
+			.saturating_add(Weight::from_parts(0, ACCOUNT_STORAGE_PROOF_SIZE))
+			.saturating_add(Self::storage_deposit_release_weight());
+		// Looking up the next suicided contract and checking whether its storage is empty.
+		let lookup_weight = db_weight.reads(2).saturating_add(Weight::from_parts(
+			0,
+			ACCOUNT_STORAGE_PROOF_SIZE.saturating_mul(2),
+		));
+		// Removing the contract from `Suicided`.
+		let suicided_weight = db_weight.writes(1);
+		// Removing the account of the contract.
+		let account_removal_weight = db_weight
+			.reads_writes(1, 2)
+			.saturating_add(Weight::from_parts(0, ACCOUNT_BASIC_PROOF_SIZE));
+		// Depositing the `SuicidedStorageCleared` event, appended to the system events along with
+		// their count.
+		let event_weight = db_weight.reads_writes(1, 2);
+		// Everything done once per contract, besides removing its storage entries.
+		let account_weight = lookup_weight
+			.saturating_add(suicided_weight)
+			.saturating_add(account_removal_weight)
+			.saturating_add(event_weight);
+
+		let mut meter = WeightMeter::with_limit(weight_limit);
+		let mut remaining = limit;
+		while remaining > 0 {
+			if meter.try_consume(account_weight).is_err() {
+				break;
+			}
+			let Some(address) = <Suicided<T>>::iter_keys().next() else {
+				break;
+			};
+
+			let max_entries = meter
+				.remaining()
+				.checked_div_per_component(&entry_weight)
+				.unwrap_or(0)
+				.min(remaining as u64) as u32;
+			let (entries, finished) = Self::clear_account_storages(address, max_entries);
+			meter.consume(entry_weight.saturating_mul(entries as u64));
+			remaining = remaining.saturating_sub(entries);
+
+			if finished {
+				<Suicided<T>>::remove(address);
+
+				let account_id = T::AddressMapping::into_account_id(address);
+				T::AccountProvider::remove_account(&account_id);
+			}
+
+			Self::deposit_event(Event::<T>::SuicidedStorageCleared {
+				address,
+				entries,
+				finished,
+			});
+
+			if !finished {
+				break;
 			}
 		}
+
+		meter.consumed()
 	}
 
 	/// Create an account.
@@ -982,13 +1711,56 @@ impl<T: Config> Pallet<T> {
 			T::AccountProvider::create_account(&account_id);
 		}
 
-		// Update metadata.
-		let meta = CodeMetadata::from_code(&code);
-		<AccountCodesMetadata<T>>::insert(address, meta);
+		Self::insert_code_metadata(address, &code);
 
 		<AccountCodes<T>>::insert(address, code);
 	}
 
+	/// Update the metadata of `address` for its new `code`, keeping [`CodesByHash`] in sync.
+	fn insert_code_metadata(address: H160, code: &[u8]) -> CodeMetadata {
+		if let Some(meta) = <AccountCodesMetadata<T>>::get(address) {
+			Self::release_code(meta.hash);
+		}
+
+		let meta = CodeMetadata::from_code(code);
+		Self::reference_code(meta.hash, code);
+		<AccountCodesMetadata<T>>::insert(address, meta);
+
+		meta
+	}
+
+	/// Add a reference to `code` with the given hash, storing it if not stored yet.
+	fn reference_code(hash: H256, code: &[u8]) {
+		<CodeReferences<T>>::mutate(hash, |references| {
+			if *references == 0 {
+				<CodesByHash<T>>::insert(hash, code);
+			}
+			*references = references.saturating_add(1);
+		});
+	}
+
+	/// Drop a reference to the code with the given hash, removing it once unused.
+	fn release_code(hash: H256) {
+		<CodeReferences<T>>::mutate_exists(hash, |references| {
+			match references.unwrap_or_default().saturating_sub(1) {
+				0 => {
+					*references = None;
+					<CodesByHash<T>>::remove(hash);
+				}
+				remaining => *references = Some(remaining),
+			}
+		});
+	}
+
+	/// Get `len` bytes of the code of `address`, starting at `offset`.
+	pub fn account_code_at_paged(address: H160, offset: u32, len: u32) -> Vec<u8> {
+		let code = <AccountCodes<T>>::get(address);
+		let start = (offset as usize).min(code.len());
+		let end = start.saturating_add(len as usize).min(code.len());
+
+		code[start..end].to_vec()
+	}
+
 	/// Get the account metadata (hash and size) from storage if it exists,
 	/// or compute it from code and store it if it doesn't exist.
 	pub fn account_code_metadata(address: H160) -> CodeMetadata {
@@ -1010,10 +1782,206 @@ impl<T: Config> Pallet<T> {
 			};
 		}
 
-		let meta = CodeMetadata::from_code(&code);
+		Self::insert_code_metadata(address, &code)
+	}
 
-		<AccountCodesMetadata<T>>::insert(address, meta);
-		meta
+	/// Run `f` with `state_overrides` applied, discarding all storage changes afterwards.
+	///
+	/// Used by the `call` and `create` runtime APIs to honor `eth_call` state overrides.
+	pub fn with_state_overrides<R>(
+		state_overrides: Vec<(H160, StateOverride)>,
+		f: impl FnOnce() -> R,
+	) -> R
+	where
+		U256: UniqueSaturatedInto<BalanceOf<T>>,
+	{
+		sp_io::storage::start_transaction();
+		for (address, state_override) in state_overrides {
+			Self::apply_state_override(address, state_override);
+		}
+		let result = f();
+		sp_io::storage::rollback_transaction();
+
+		result
+	}
+
+	/// Credit `source` with the balance it misses to afford `value` and a fee of
+	/// `max_fee_per_gas` per gas up to `gas_limit`, so that estimating the gas of its transaction
+	/// isn't cut short.
+	///
+	/// Only meant for the `call` and `create` runtime APIs when estimating, within
+	/// [`Self::with_state_overrides`] that discards the credit afterwards.
+	pub fn credit_estimate_balance(
+		source: H160,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+	) where
+		U256: UniqueSaturatedInto<BalanceOf<T>>,
+	{
+		let fee = max_fee_per_gas
+			.unwrap_or_default()
+			.saturating_mul(gas_limit.into());
+		let required = value.saturating_add(fee);
+		let (account, _) = Self::account_basic(&source);
+		if account.balance >= required {
+			return;
+		}
+
+		// The reducible balance keeps the existential deposit, on top of the required balance.
+		let account_id = T::AddressMapping::into_account_id(source);
+		let free_balance = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+			T::Currency::free_balance(&account_id),
+		));
+		let existential_deposit = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+			<T::Currency as Currency<AccountIdOf<T>>>::minimum_balance(),
+		));
+		let balance = free_balance
+			.saturating_add(required.saturating_sub(account.balance))
+			.max(required.saturating_add(existential_deposit));
+		let _ = T::Currency::make_free_balance_be(&account_id, balance.unique_saturated_into());
+	}
+
+	fn apply_state_override(address: H160, state_override: StateOverride)
+	where
+		U256: UniqueSaturatedInto<BalanceOf<T>>,
+	{
+		let account_id = T::AddressMapping::into_account_id(address);
+		if let Some(balance) = state_override.balance {
+			let _ = T::Currency::make_free_balance_be(&account_id, balance.unique_saturated_into());
+		}
+		if let Some(nonce) = state_override.nonce {
+			T::AccountProvider::set_account_nonce(
+				&account_id,
+				UniqueSaturatedInto::<u128>::unique_saturated_into(nonce).unique_saturated_into(),
+			);
+		}
+		if let Some(code) = state_override.code {
+			Self::insert_code_metadata(address, &code);
+			<AccountCodes<T>>::insert(address, code);
+		}
+		if let Some(state) = state_override.state {
+			let _ = Self::clear_account_storages(address, u32::MAX);
+			for (index, value) in state {
+				<AccountStorages<T>>::insert(address, index, value);
+			}
+		}
+		for (index, value) in state_override.state_diff.unwrap_or_default() {
+			<AccountStorages<T>>::insert(address, index, value);
+		}
+	}
+
+	/// Index of the current gas quota period, `None` if gas quotas are disabled.
+	fn gas_quota_period() -> Option<u32> {
+		let period = T::GasQuotaPeriod::get();
+		if T::GasQuota::get() == 0 || period == 0 {
+			return None;
+		}
+		let block_number: u32 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
+		Some(block_number / period)
+	}
+
+	/// Gas left in the quota of `address` for the current period, `None` if gas quotas are
+	/// disabled.
+	pub fn gas_quota_remaining(address: &H160) -> Option<U256> {
+		let period = Self::gas_quota_period()?;
+		let used = <GasQuotaUsage<T>>::get(period, address);
+		Some(T::GasQuota::get().saturating_sub(used).into())
+	}
+
+	/// Deposit the event of a call to `target` that exited with `exit_reason`, the `output` of
+	/// reverted calls being truncated to [`Config::MaxRevertOutputLength`].
+	fn deposit_executed_event(target: H160, exit_reason: &ExitReason, mut output: Vec<u8>) {
+		let event = match exit_reason {
+			ExitReason::Succeed(_) => Event::<T>::Executed { address: target },
+			ExitReason::Revert(_) => {
+				output.truncate(T::MaxRevertOutputLength::get() as usize);
+				Event::<T>::ExecutedFailed {
+					address: target,
+					output,
+				}
+			}
+			_ => Event::<T>::ExecutedFailed {
+				address: target,
+				output: Vec::new(),
+			},
+		};
+		Self::deposit_event(event);
+	}
+
+	/// Add `gas` to the gas used by the current block.
+	pub fn record_block_gas_used(gas: U256) {
+		<BlockGasUsed<T>>::mutate(|used| *used = used.saturating_add(gas));
+	}
+
+	/// Weight of [`Self::record_gas_quota_usage`], zero if gas quotas are disabled.
+	pub fn gas_quota_usage_weight() -> Weight {
+		if Self::gas_quota_period().is_none() {
+			return Weight::zero();
+		}
+		Weight::from_parts(
+			T::DbWeight::get().reads_writes(2, 2).ref_time(),
+			GAS_QUOTA_USAGE_PROOF_SIZE.saturating_add(GAS_QUOTA_PERIOD_PROOF_SIZE),
+		)
+	}
+
+	/// Record `gas` used by `address` against its quota for the current period.
+	pub fn record_gas_quota_usage(address: &H160, gas: u64) {
+		if let Some(period) = Self::gas_quota_period() {
+			<GasQuotaUsage<T>>::mutate(period, address, |used| *used = used.saturating_add(gas));
+			if !<GasQuotaPeriods<T>>::contains_key(period) {
+				<GasQuotaPeriods<T>>::insert(period, ());
+			}
+		}
+	}
+
+	/// Remove the [`GasQuotaUsage`] entries of the past gas quota periods, or of all the periods
+	/// if gas quotas are disabled, within the given weight limit. Returns the consumed weight.
+	pub fn clear_gas_quota_usage(weight_limit: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		// Removing a single usage entry.
+		let entry_weight = db_weight
+			.reads_writes(1, 1)
+			.saturating_add(Weight::from_parts(0, GAS_QUOTA_USAGE_PROOF_SIZE));
+		// Looking up a past period among the periods with entries, checking whether all its entries
+		// are removed and removing it.
+		let period_weight = db_weight
+			.reads_writes(3, 1)
+			.saturating_add(Weight::from_parts(
+				0,
+				GAS_QUOTA_PERIOD_PROOF_SIZE
+					.saturating_mul(2)
+					.saturating_add(GAS_QUOTA_USAGE_PROOF_SIZE),
+			));
+
+		let current = Self::gas_quota_period();
+		let mut meter = WeightMeter::with_limit(weight_limit);
+		loop {
+			if meter.try_consume(period_weight).is_err() {
+				break;
+			}
+			let Some(period) =
+				<GasQuotaPeriods<T>>::iter_keys().find(|period| Some(*period) != current)
+			else {
+				break;
+			};
+
+			let max_entries = meter
+				.remaining()
+				.checked_div_per_component(&entry_weight)
+				.unwrap_or(0) as usize;
+			let entries = <GasQuotaUsage<T>>::drain_prefix(period)
+				.take(max_entries)
+				.count() as u64;
+			meter.consume(entry_weight.saturating_mul(entries));
+
+			if <GasQuotaUsage<T>>::iter_key_prefix(period).next().is_some() {
+				break;
+			}
+			<GasQuotaPeriods<T>>::remove(period);
+		}
+
+		meter.consumed()
 	}
 
 	/// Get the account basic in EVM format.
@@ -1041,6 +2009,17 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+/// [`BlockUtilization`] based on the gas used by the EVM executions of the current block, against
+/// [`Config::BlockGasLimit`].
+impl<T: Config> BlockUtilization for Pallet<T> {
+	fn utilization() -> Permill {
+		let used: u64 = <BlockGasUsed<T>>::get().unique_saturated_into();
+		let limit: u64 = T::BlockGasLimit::get().unique_saturated_into();
+
+		Permill::from_rational(used, limit)
+	}
+}
+
 /// Handle withdrawing, refunding and depositing of transaction fees.
 /// Similar to `OnChargeTransaction` of `pallet_transaction_payment`
 pub trait OnChargeEVMTransaction<T: Config> {
@@ -1234,6 +2213,96 @@ where
 	}
 }
 
+/// Conversion of EVM fees, denominated in the native currency, into amounts of another asset.
+pub trait FeeAssetConversion<Balance> {
+	/// Convert the native `fee` into an amount of the fee asset, `None` if it can't be converted.
+	fn to_asset_balance(fee: U256) -> Option<Balance>;
+}
+
+/// Implements transaction payment in the asset `A` of a pallet implementing the [`fungibles`]
+/// traits (eg. pallet_assets) using an unbalance handler (implementing [`OnUnbalanced`]).
+///
+/// Fees are converted into amounts of the asset with `R`. Only fees are paid in the asset, value
+/// transfers still use [`Config::Currency`].
+pub struct EVMFungiblesAdapter<F, A, R, OU>(core::marker::PhantomData<(F, A, R, OU)>);
+
+impl<T, F, A, R, OU> OnChargeEVMTransaction<T> for EVMFungiblesAdapter<F, A, R, OU>
+where
+	T: Config,
+	F: fungibles::Balanced<AccountIdOf<T>>,
+	A: Get<<F as fungibles::Inspect<AccountIdOf<T>>>::AssetId>,
+	R: FeeAssetConversion<<F as fungibles::Inspect<AccountIdOf<T>>>::Balance>,
+	OU: OnUnbalanced<fungibles::Credit<AccountIdOf<T>, F>>,
+{
+	// Kept type as Option to satisfy bound of Default
+	type LiquidityInfo = Option<fungibles::Credit<AccountIdOf<T>, F>>;
+
+	fn withdraw_fee(who: &H160, fee: U256) -> Result<Self::LiquidityInfo, Error<T>> {
+		if fee.is_zero() {
+			return Ok(None);
+		}
+		let amount = R::to_asset_balance(fee).ok_or(Error::<T>::FeeOverflow)?;
+		let account_id = T::AddressMapping::into_account_id(*who);
+		let imbalance = F::withdraw(
+			A::get(),
+			&account_id,
+			amount,
+			Precision::Exact,
+			Preservation::Preserve,
+			Fortitude::Polite,
+		)
+		.map_err(|_| Error::<T>::BalanceLow)?;
+		Ok(Some(imbalance))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &H160,
+		corrected_fee: U256,
+		base_fee: U256,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Self::LiquidityInfo {
+		if let Some(paid) = already_withdrawn {
+			let account_id = T::AddressMapping::into_account_id(*who);
+			let asset = paid.asset();
+
+			// Calculate how much refund we should return, nothing if the fee can't be converted.
+			let refund_amount = R::to_asset_balance(corrected_fee)
+				.map(|corrected_fee| paid.peek().saturating_sub(corrected_fee))
+				.unwrap_or_default();
+			// refund to the account that paid the fees.
+			let refund_imbalance = F::deposit(
+				asset.clone(),
+				&account_id,
+				refund_amount,
+				Precision::BestEffort,
+			)
+			.unwrap_or_else(|_| fungibles::Debt::<AccountIdOf<T>, F>::zero(asset.clone()));
+
+			// merge the imbalance caused by paying the fees and refunding parts of it again.
+			let adjusted_paid = paid
+				.offset(refund_imbalance)
+				.ok()
+				.and_then(|same_or_other| same_or_other.same().ok())
+				.unwrap_or_else(|| fungibles::Credit::<AccountIdOf<T>, F>::zero(asset));
+
+			let base_fee = R::to_asset_balance(base_fee).unwrap_or_else(|| adjusted_paid.peek());
+			let (base_fee, tip) = adjusted_paid.split(base_fee);
+			// Handle base fee. Can be either burned, rationed, etc ...
+			OU::on_unbalanced(base_fee);
+			return Some(tip);
+		}
+		None
+	}
+
+	fn pay_priority_fee(tip: Self::LiquidityInfo) {
+		// Default Ethereum behaviour: issue the tip to the block author.
+		if let Some(tip) = tip {
+			let account_id = T::AddressMapping::into_account_id(<Pallet<T>>::find_author());
+			let _ = F::deposit(tip.asset(), &account_id, tip.peek(), Precision::BestEffort);
+		}
+	}
+}
+
 /// Implementation for () does not specify what to do with imbalance
 impl<T> OnChargeEVMTransaction<T> for ()
 where
@@ -1284,6 +2353,80 @@ impl<T> OnCreate<T> for Tuple {
 	}
 }
 
+/// Derivation of the address of a contract created by a top-level `create` or `create2`.
+///
+/// Returning `None` falls back to the standard derivation. Contracts created from within the EVM
+/// by the `CREATE` and `CREATE2` opcodes always use the standard derivation.
+pub trait CreateAddressScheme<T> {
+	fn create_address(scheme: CreateScheme) -> Option<H160>;
+}
+
+impl<T> CreateAddressScheme<T> for () {
+	fn create_address(_scheme: CreateScheme) -> Option<H160> {
+		None
+	}
+}
+
+/// Deterministic address derivation for system contracts deployed by `Deployer`.
+///
+/// Addresses are namespaced by `Namespace`, e.g. the chain ID, so that the same deployment lands
+/// on distinct addresses across chains. Contracts created by any other source keep the standard
+/// derivation.
+pub struct NamespacedDeployer<Deployer, Namespace>(
+	core::marker::PhantomData<(Deployer, Namespace)>,
+);
+
+impl<Deployer, Namespace> NamespacedDeployer<Deployer, Namespace>
+where
+	Namespace: Get<u64>,
+{
+	/// Domain separator of the derived addresses.
+	pub const PREFIX: &'static [u8] = b"frontier:deployer";
+
+	/// Address of the contract created with `discriminant`, the nonce of the deployer for `CREATE`
+	/// or the hash of the salt and init code for `CREATE2`.
+	pub fn derive(kind: u8, discriminant: H256) -> H160 {
+		let mut preimage = Vec::with_capacity(Self::PREFIX.len() + 8 + 1 + 32);
+		preimage.extend_from_slice(Self::PREFIX);
+		preimage.extend_from_slice(&Namespace::get().to_be_bytes());
+		preimage.push(kind);
+		preimage.extend_from_slice(discriminant.as_bytes());
+		H256::from(sp_io::hashing::keccak_256(&preimage)).into()
+	}
+}
+
+impl<T, Deployer, Namespace> CreateAddressScheme<T> for NamespacedDeployer<Deployer, Namespace>
+where
+	T: Config,
+	Deployer: Get<H160>,
+	Namespace: Get<u64>,
+{
+	fn create_address(scheme: CreateScheme) -> Option<H160> {
+		match scheme {
+			CreateScheme::Legacy { caller } if caller == Deployer::get() => {
+				let (account, _) = Pallet::<T>::account_basic(&caller);
+				let mut nonce = [0u8; 32];
+				account.nonce.to_big_endian(&mut nonce);
+				Some(Self::derive(0, H256::from(nonce)))
+			}
+			CreateScheme::Create2 {
+				caller,
+				code_hash,
+				salt,
+			} if caller == Deployer::get() => {
+				let mut preimage = [0u8; 64];
+				preimage[..32].copy_from_slice(salt.as_bytes());
+				preimage[32..].copy_from_slice(code_hash.as_bytes());
+				Some(Self::derive(
+					1,
+					H256::from(sp_io::hashing::keccak_256(&preimage)),
+				))
+			}
+			_ => None,
+		}
+	}
+}
+
 /// EVM account provider based on the [`frame_system`] accounts.
 ///
 /// Uses standard Substrate accounts system to hold EVM accounts.
@@ -1301,6 +2444,10 @@ impl<T: frame_system::Config> AccountProvider for FrameSystemAccountProvider<T>
 		frame_system::Pallet::<T>::inc_account_nonce(who)
 	}
 
+	fn set_account_nonce(who: &Self::AccountId, nonce: Self::Nonce) {
+		frame_system::Account::<T>::mutate(who, |account| account.nonce = nonce)
+	}
+
 	fn create_account(who: &Self::AccountId) {
 		let _ = frame_system::Pallet::<T>::inc_sufficients(who);
 	}
@@ -0,0 +1,111 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations of the EVM pallet.
+
+use core::marker::PhantomData;
+use frame_support::{
+	migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+	traits::{Get, GetStorageVersion, StorageVersion},
+	weights::{Weight, WeightMeter},
+};
+use sp_core::H160;
+// Frontier
+use fp_evm::{ACCOUNT_CODES_KEY_SIZE, ACCOUNT_CODES_METADATA_PROOF_SIZE};
+
+use crate::{AccountCodes, AccountCodesMetadata, CodeMetadata, Config, Pallet};
+
+/// `CodeReferences` entry. 32 (key) + 4 (references)
+const CODE_REFERENCES_PROOF_SIZE: u64 = 36;
+
+/// Multi-block migration storing the code of the existing contracts in
+/// [`CodesByHash`](crate::CodesByHash), referenced from [`CodeReferences`](crate::CodeReferences).
+///
+/// Contracts deployed before [`CodesByHash`](crate::CodesByHash) are only stored in
+/// [`AccountCodes`], so `code_by_hash` doesn't know about them until migrated. Each distinct code
+/// is stored once more, so the migration grows the state by the size of all the distinct deployed
+/// codes, plus 36 bytes per distinct code for its reference count. One contract is migrated per
+/// step, in as many steps as the block weight allows.
+///
+/// Register it with `pallet-migrations`, which keeps the chain from processing transactions
+/// until all the contracts are migrated.
+pub struct CodesByHashMigration<T>(PhantomData<T>);
+
+impl<T: Config> CodesByHashMigration<T> {
+	/// Weight of migrating a single contract, with its code of at most [`Config::MaxCodeSize`].
+	// Not benchmarked yet, only the storage accesses are accounted for.
+	pub fn step_weight() -> Weight {
+		Weight::from_parts(
+			0,
+			ACCOUNT_CODES_KEY_SIZE
+				.saturating_add(T::MaxCodeSize::get().into())
+				.saturating_add(ACCOUNT_CODES_METADATA_PROOF_SIZE)
+				.saturating_add(CODE_REFERENCES_PROOF_SIZE),
+		)
+		.saturating_add(T::DbWeight::get().reads_writes(3, 3))
+	}
+}
+
+impl<T: Config> SteppedMigration for CodesByHashMigration<T> {
+	type Cursor = H160;
+	type Identifier = MigrationId<10>;
+
+	fn id() -> Self::Identifier {
+		MigrationId {
+			pallet_id: *b"pallet-evm",
+			version_from: 0,
+			version_to: 1,
+		}
+	}
+
+	fn step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		if Pallet::<T>::on_chain_storage_version() != Self::id().version_from as u16 {
+			return Ok(None);
+		}
+
+		let required = Self::step_weight();
+		if meter.remaining().any_lt(required) {
+			return Err(SteppedMigrationError::InsufficientWeight { required });
+		}
+
+		while meter.try_consume(required).is_ok() {
+			let mut iter = match cursor {
+				Some(last) => <AccountCodes<T>>::iter_from(<AccountCodes<T>>::hashed_key_for(last)),
+				None => <AccountCodes<T>>::iter(),
+			};
+
+			let Some((address, code)) = iter.next() else {
+				StorageVersion::new(Self::id().version_to as u16).put::<Pallet<T>>();
+				return Ok(None);
+			};
+
+			// The metadata of the contracts only misses the code reference, keep the existing one.
+			let meta = <AccountCodesMetadata<T>>::get(address).unwrap_or_else(|| {
+				let meta = CodeMetadata::from_code(&code);
+				<AccountCodesMetadata<T>>::insert(address, meta);
+				meta
+			});
+			Pallet::<T>::reference_code(meta.hash, &code);
+			cursor = Some(address);
+		}
+
+		Ok(cursor)
+	}
+}
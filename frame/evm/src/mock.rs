@@ -17,7 +17,7 @@
 
 //! Test mock for unit tests and benchmarking
 
-use frame_support::{derive_impl, parameter_types, weights::Weight};
+use frame_support::{derive_impl, parameter_types, traits::Contains, weights::Weight};
 use sp_core::{H160, U256};
 
 use crate::{
@@ -30,7 +30,8 @@ frame_support::construct_runtime! {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
-		EVM: crate::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		EVM: crate::{Pallet, Call, Storage, Config<T>, Event<T>, HoldReason},
 	}
 }
 
@@ -49,7 +50,7 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 0;
+	pub static ExistentialDeposit: u64 = 0;
 }
 #[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
 impl pallet_balances::Config for Test {
@@ -60,8 +61,26 @@ impl pallet_balances::Config for Test {
 #[derive_impl(pallet_timestamp::config_preludes::TestDefaultConfig)]
 impl pallet_timestamp::Config for Test {}
 
+#[derive_impl(pallet_assets::config_preludes::TestDefaultConfig)]
+impl pallet_assets::Config for Test {
+	type Currency = Balances;
+	type CreateOrigin =
+		frame_support::traits::AsEnsureOriginWithArg<frame_system::EnsureSigned<Self::AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Freezer = ();
+}
+
 parameter_types! {
 	pub MockPrecompiles: MockPrecompileSet = MockPrecompileSet;
+	pub static MaxCodeSize: u32 = 24 * 1024;
+	pub static MaxInitCodeSize: u32 = 48 * 1024;
+	pub static SuicideIdleClearLimit: u32 = 0;
+	pub static Permissioned: bool = false;
+	pub static StorageDepositPerSlot: U256 = U256::zero();
+	pub static GasQuota: u64 = 0;
+	pub static GasQuotaPeriod: u32 = 10;
+	pub SystemDeployer: H160 = H160::from_low_u64_be(0xde9);
+	pub const ChainId: u64 = 42;
 }
 
 #[derive_impl(crate::config_preludes::TestDefaultConfig)]
@@ -74,6 +93,27 @@ impl crate::Config for Test {
 	type PrecompilesValue = MockPrecompiles;
 	type Runner = crate::runner::stack::Runner<Self>;
 	type Timestamp = Timestamp;
+	type MaxCodeSize = MaxCodeSize;
+	type MaxInitCodeSize = MaxInitCodeSize;
+	type SuicideIdleClearLimit = SuicideIdleClearLimit;
+	type CreateOriginFilter = PermissionedFilter<crate::AllowedCreators<Self>>;
+	type CallOriginFilter = PermissionedFilter<crate::AllowedCallers<Self>>;
+	type StorageDepositPerSlot = StorageDepositPerSlot;
+	type GasQuota = GasQuota;
+	type GasQuotaPeriod = GasQuotaPeriod;
+	type CreateAddressScheme = crate::NamespacedDeployer<SystemDeployer, ChainId>;
+
+	fn config() -> &'static crate::EvmConfig {
+		crate::Pallet::<Self>::hardfork().config()
+	}
+}
+
+/// Allows everyone, unless `Permissioned` is set.
+pub struct PermissionedFilter<F>(core::marker::PhantomData<F>);
+impl<F: Contains<H160>> Contains<H160> for PermissionedFilter<F> {
+	fn contains(address: &H160) -> bool {
+		!Permissioned::get() || F::contains(address)
+	}
 }
 
 pub struct FixedGasPrice;
@@ -30,7 +30,7 @@ pub struct RunnerError<E: Into<sp_runtime::DispatchError>> {
 }
 
 pub trait Runner<T: Config> {
-	type Error: Into<sp_runtime::DispatchError>;
+	type Error: Into<sp_runtime::DispatchError> + From<crate::Error<T>>;
 
 	fn validate(
 		source: H160,
@@ -65,6 +65,34 @@ pub trait Runner<T: Config> {
 		config: &evm::Config,
 	) -> Result<CallInfo, RunnerError<Self::Error>>;
 
+	/// Like [`Runner::call`], with the fee paid by `sponsor` out of its
+	/// [`SponsorAllowances`](crate::SponsorAllowances) for `source`.
+	///
+	/// Fails with [`Error::SponsoredCallUnsupported`](crate::Error::SponsoredCallUnsupported)
+	/// unless implemented.
+	fn call_sponsored(
+		_sponsor: H160,
+		_source: H160,
+		_target: H160,
+		_input: Vec<u8>,
+		_value: U256,
+		_gas_limit: u64,
+		_max_fee_per_gas: Option<U256>,
+		_max_priority_fee_per_gas: Option<U256>,
+		_nonce: Option<U256>,
+		_access_list: Vec<(H160, Vec<H256>)>,
+		_is_transactional: bool,
+		_validate: bool,
+		_weight_limit: Option<Weight>,
+		_proof_size_base_cost: Option<u64>,
+		_config: &evm::Config,
+	) -> Result<CallInfo, RunnerError<Self::Error>> {
+		Err(RunnerError {
+			error: crate::Error::<T>::SponsoredCallUnsupported.into(),
+			weight: Weight::zero(),
+		})
+	}
+
 	fn create(
 		source: H160,
 		init: Vec<u8>,
@@ -18,7 +18,6 @@
 //! EVM stack-based runner.
 
 use alloc::{
-	boxed::Box,
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	vec::Vec,
 };
@@ -27,11 +26,12 @@ use evm::{
 	backend::Backend as BackendT,
 	executor::stack::{Accessed, StackExecutor, StackState as StackStateT, StackSubstateMetadata},
 	gasometer::{GasCost, StorageTarget},
-	ExitError, ExitReason, ExternalOperation, Opcode, Transfer,
+	ExitError, ExitReason, ExitRevert, ExternalOperation, Opcode, Transfer,
 };
 // Substrate
 use frame_support::{
 	traits::{
+		fungible::MutateHold,
 		tokens::{currency::Currency, ExistenceRequirement},
 		Get, Time,
 	},
@@ -41,22 +41,71 @@ use sp_core::{H160, H256, U256};
 use sp_runtime::traits::UniqueSaturatedInto;
 // Frontier
 use fp_evm::{
-	AccessedStorage, CallInfo, CreateInfo, ExecutionInfoV2, IsPrecompileResult, Log, PrecompileSet,
-	Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_CODES_KEY_SIZE,
-	ACCOUNT_CODES_METADATA_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE, IS_EMPTY_CHECK_PROOF_SIZE,
+	AccessedStorage, CallInfo, CreateInfo, ExecutionInfoV2, IsPrecompileResult, Log,
+	PrecompileFailure, PrecompileHandle, PrecompileResult, PrecompileSet,
+	TransactionValidationError, Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE,
+	ACCOUNT_CODES_KEY_SIZE, ACCOUNT_CODES_METADATA_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE,
+	IS_EMPTY_CHECK_PROOF_SIZE, PAUSED_CONTRACTS_PROOF_SIZE, STORAGE_DEPOSIT_PROOF_SIZE,
 	WRITE_PROOF_SIZE,
 };
 
 use super::meter::StorageMeter;
 use crate::{
 	runner::Runner as RunnerT, AccountCodes, AccountCodesMetadata, AccountProvider,
-	AccountStorages, AddressMapping, BalanceOf, BlockHashMapping, Config, Error, Event,
-	FeeCalculator, OnChargeEVMTransaction, OnCreate, Pallet, RunnerError,
+	AccountStorages, AddressMapping, BalanceOf, BlockHashMapping, Config, CreateAddressScheme,
+	DepositBalanceOf, Error, Event, FeeCalculator, HoldReason, OnChargeEVMTransaction, OnCreate,
+	Pallet, PausedContracts, RunnerError, StorageDeposits,
 };
 
 #[cfg(feature = "forbid-evm-reentrancy")]
 environmental::environmental!(IN_EVM: bool);
 
+/// Selector of the `ContractPaused(address)` error calls into [`PausedContracts`] revert with.
+pub const CONTRACT_PAUSED_SELECTOR: [u8; 4] = [0x81, 0x99, 0x0f, 0xd9];
+
+/// ABI encoded `ContractPaused(address)` error of calls into the paused contract at `address`.
+pub fn contract_paused_output(address: H160) -> Vec<u8> {
+	let mut output = CONTRACT_PAUSED_SELECTOR.to_vec();
+	output.extend_from_slice(H256::from(address).as_bytes());
+	output
+}
+
+/// Precompile set reverting calls into [`PausedContracts`], delegating to
+/// [`Config::PrecompilesType`] otherwise.
+///
+/// The [`PausedContracts`] read of each call is charged to the weight and proof size meters.
+pub struct PausablePrecompiles<T: Config>(T::PrecompilesType);
+
+impl<T: Config> PausablePrecompiles<T> {
+	pub fn new(precompiles: T::PrecompilesType) -> Self {
+		Self(precompiles)
+	}
+}
+
+impl<T: Config> PrecompileSet for PausablePrecompiles<T> {
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		if let Err(exit_status) = handle.record_external_cost(
+			Some(T::DbWeight::get().reads(1).ref_time()),
+			Some(PAUSED_CONTRACTS_PROOF_SIZE),
+			None,
+		) {
+			return Some(Err(PrecompileFailure::Error { exit_status }));
+		}
+		let address = handle.code_address();
+		if <PausedContracts<T>>::contains_key(address) {
+			return Some(Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output: contract_paused_output(address),
+			}));
+		}
+		self.0.execute(handle)
+	}
+
+	fn is_precompile(&self, address: H160, remaining_gas: u64) -> IsPrecompileResult {
+		self.0.is_precompile(address, remaining_gas)
+	}
+}
+
 #[derive(Default)]
 pub struct Runner<T: Config> {
 	_marker: PhantomData<T>,
@@ -70,12 +119,14 @@ where
 	/// Execute an already validated EVM operation.
 	fn execute<'config, 'precompiles, F, R>(
 		source: H160,
+		sponsor: Option<H160>,
+		target: Option<H160>,
 		value: U256,
 		gas_limit: u64,
 		max_fee_per_gas: Option<U256>,
 		max_priority_fee_per_gas: Option<U256>,
 		config: &'config evm::Config,
-		precompiles: &'precompiles T::PrecompilesType,
+		precompiles: &'precompiles PausablePrecompiles<T>,
 		is_transactional: bool,
 		weight_limit: Option<Weight>,
 		proof_size_base_cost: Option<u64>,
@@ -87,7 +138,7 @@ where
 				'config,
 				'precompiles,
 				SubstrateStackState<'_, 'config, T>,
-				T::PrecompilesType,
+				PausablePrecompiles<T>,
 			>,
 		) -> (ExitReason, R),
 		R: Default,
@@ -97,6 +148,8 @@ where
 		#[cfg(not(feature = "forbid-evm-reentrancy"))]
 		let res = Self::execute_inner(
 			source,
+			sponsor,
+			target,
 			value,
 			gas_limit,
 			max_fee_per_gas,
@@ -135,6 +188,8 @@ where
 
 			Self::execute_inner(
 				source,
+				sponsor,
+				target,
 				value,
 				gas_limit,
 				max_fee_per_gas,
@@ -156,12 +211,14 @@ where
 	// Execute an already validated EVM operation.
 	fn execute_inner<'config, 'precompiles, F, R>(
 		source: H160,
+		sponsor: Option<H160>,
+		target: Option<H160>,
 		value: U256,
 		mut gas_limit: u64,
 		max_fee_per_gas: Option<U256>,
 		max_priority_fee_per_gas: Option<U256>,
 		config: &'config evm::Config,
-		precompiles: &'precompiles T::PrecompilesType,
+		precompiles: &'precompiles PausablePrecompiles<T>,
 		is_transactional: bool,
 		f: F,
 		base_fee: U256,
@@ -175,19 +232,39 @@ where
 				'config,
 				'precompiles,
 				SubstrateStackState<'_, 'config, T>,
-				T::PrecompilesType,
+				PausablePrecompiles<T>,
 			>,
 		) -> (ExitReason, R),
 		R: Default,
 	{
 		// Used to record the external costs in the evm through the StackState implementation
-		let maybe_weight_info =
+		let mut maybe_weight_info =
 			WeightInfo::new_from_weight_limit(weight_limit, proof_size_base_cost).map_err(
 				|_| RunnerError {
 					error: Error::<T>::GasLimitTooLow,
 					weight,
 				},
 			)?;
+
+		// The gas quota usage of the source is recorded after transactional executions.
+		let is_estimate = config.estimate;
+		let weight = if is_transactional && !is_estimate {
+			let gas_quota_weight = Pallet::<T>::gas_quota_usage_weight();
+			if let Some(weight_info) = maybe_weight_info.as_mut() {
+				weight_info
+					.try_record_ref_time_or_fail(gas_quota_weight.ref_time())
+					.and_then(|_| {
+						weight_info.try_record_proof_size_or_fail(gas_quota_weight.proof_size())
+					})
+					.map_err(|_| RunnerError {
+						error: Error::<T>::GasLimitTooLow,
+						weight,
+					})?;
+			}
+			weight.saturating_add(gas_quota_weight)
+		} else {
+			weight
+		};
 		// The precompile check is only used for transactional invocations. However, here we always
 		// execute the check, because the check has side effects.
 		match precompiles.is_precompile(source, gas_limit) {
@@ -208,20 +285,18 @@ where
 			}
 		};
 
-		// Only check the restrictions of EIP-3607 if the source of the EVM operation is from an external transaction.
-		// If the source of this EVM operation is from an internal call, like from `eth_call` or `eth_estimateGas` RPC,
-		// we will skip the checks for the EIP-3607.
-		//
-		// EIP-3607: https://eips.ethereum.org/EIPS/eip-3607
-		// Do not allow transactions for which `tx.sender` has any code deployed.
-		if is_transactional && !<AccountCodes<T>>::get(source).is_empty() {
-			return Err(RunnerError {
-				error: Error::<T>::TransactionMustComeFromEOA,
-				weight,
-			});
+		// Only check the restrictions of EIP-3607 and the origin filters if the source of the EVM
+		// operation is from an external transaction. If the source of this EVM operation is from an
+		// internal call, like from `eth_call` or `eth_estimateGas` RPC, we will skip these checks.
+		if is_transactional && !is_estimate {
+			Self::ensure_source_has_no_code(source, weight)?;
+			Pallet::<T>::ensure_origin_allowed(&source, target).map_err(|error| RunnerError {
+				error: error.into(),
+				weight: weight.saturating_add(T::DbWeight::get().reads(1)),
+			})?;
 		}
 
-		let total_fee_per_gas = if is_transactional {
+		let total_fee_per_gas = if is_transactional && !is_estimate {
 			match (max_fee_per_gas, max_priority_fee_per_gas) {
 				// Zero max_fee_per_gas for validated transactional calls exist in XCM -> EVM
 				// because fees are already withdrawn in the xcm-executor.
@@ -245,7 +320,8 @@ where
 				}
 			}
 		} else {
-			// Gas price check is skipped for non-transactional calls or creates
+			// Gas price check is skipped for non-transactional calls or creates, and when
+			// estimating gas
 			Default::default()
 		};
 
@@ -258,8 +334,19 @@ where
 					weight,
 				})?;
 
-		// Deduct fee from the `source` account. Returns `None` if `total_fee` is Zero.
-		let fee = T::OnChargeTransaction::withdraw_fee(&source, total_fee)
+		// Fees of sponsored calls are paid by the sponsor, out of its allowance for `source`.
+		let fee_payer = sponsor.unwrap_or(source);
+		if let Some(sponsor) = sponsor {
+			if <SponsorAllowances<T>>::get(sponsor, source) < total_fee {
+				return Err(RunnerError {
+					error: Error::<T>::SponsorAllowanceTooLow,
+					weight,
+				});
+			}
+		}
+
+		// Deduct fee from the `fee_payer` account. Returns `None` if `total_fee` is Zero.
+		let fee = T::OnChargeTransaction::withdraw_fee(&fee_payer, total_fee)
 			.map_err(|e| RunnerError { error: e, weight })?;
 
 		let vicinity = Vicinity {
@@ -348,7 +435,7 @@ where
 		// Tip 5 * 6 = 30.
 		// Burned 200 - (160 + 30) = 10. Which is equivalent to gas_used * base_fee.
 		let actual_priority_fee = T::OnChargeTransaction::correct_and_deposit_fee(
-			&source,
+			&fee_payer,
 			// Actual fee after evm execution, including tip.
 			actual_fee,
 			// Base fee.
@@ -357,6 +444,17 @@ where
 			fee,
 		);
 		T::OnChargeTransaction::pay_priority_fee(actual_priority_fee);
+		if let Some(sponsor) = sponsor {
+			<SponsorAllowances<T>>::mutate(sponsor, source, |allowance| {
+				*allowance = allowance.saturating_sub(actual_fee)
+			});
+		}
+		if is_transactional && !is_estimate {
+			Pallet::<T>::record_gas_quota_usage(&source, effective_gas.unique_saturated_into());
+		}
+		if !is_estimate {
+			Pallet::<T>::record_block_gas_used(effective_gas);
+		}
 
 		let state = executor.into_state();
 
@@ -399,16 +497,29 @@ where
 			logs: state.substate.logs,
 		})
 	}
-}
 
-impl<T: Config> RunnerT<T> for Runner<T>
-where
-	BalanceOf<T>: TryFrom<U256> + Into<U256>,
-{
-	type Error = Error<T>;
+	/// EIP-3607: https://eips.ethereum.org/EIPS/eip-3607
+	/// Do not allow transactions for which `tx.sender` has any code deployed.
+	fn ensure_source_has_no_code(
+		source: H160,
+		weight: Weight,
+	) -> Result<(), RunnerError<Error<T>>> {
+		if <AccountCodes<T>>::decode_len(source).unwrap_or(0) > 0 {
+			return Err(RunnerError {
+				error: TransactionValidationError::SenderHasCode.into(),
+				weight: weight.saturating_add(T::DbWeight::get().reads(1)),
+			});
+		}
+		Ok(())
+	}
 
-	fn validate(
+	/// Validate an EVM operation whose fee is paid by `sponsor`, or by `source` if unset.
+	///
+	/// `evm_config` is expected to have the code limits applied, see
+	/// [`Pallet::config_with_code_limits`].
+	fn validate_inner(
 		source: H160,
+		sponsor: Option<H160>,
 		target: Option<H160>,
 		input: Vec<u8>,
 		value: U256,
@@ -421,18 +532,25 @@ where
 		weight_limit: Option<Weight>,
 		proof_size_base_cost: Option<u64>,
 		evm_config: &evm::Config,
-	) -> Result<(), RunnerError<Self::Error>> {
+	) -> Result<(), RunnerError<Error<T>>> {
 		let (base_fee, mut weight) = T::FeeCalculator::min_gas_price();
 		let (source_account, inner_weight) = Pallet::<T>::account_basic(&source);
 		weight = weight.saturating_add(inner_weight);
+		let sponsor_account = sponsor.map(|sponsor| {
+			let (sponsor_account, inner_weight) = Pallet::<T>::account_basic(&sponsor);
+			weight = weight.saturating_add(inner_weight);
+			sponsor_account
+		});
 
-		let _ = fp_evm::CheckEvmTransaction::<Self::Error>::new(
+		let _ = fp_evm::CheckEvmTransaction::<Error<T>>::new(
 			fp_evm::CheckEvmTransactionConfig {
 				evm_config,
 				block_gas_limit: T::BlockGasLimit::get(),
 				base_fee,
 				chain_id: T::ChainId::get(),
 				is_transactional,
+				is_estimate: evm_config.estimate,
+				max_nonce_gap: None,
 			},
 			fp_evm::CheckEvmTransactionInput {
 				chain_id: Some(T::ChainId::get()),
@@ -451,10 +569,59 @@ where
 		)
 		.validate_in_block_for(&source_account)
 		.and_then(|v| v.with_base_fee())
-		.and_then(|v| v.with_balance_for(&source_account))
+		.and_then(|v| match &sponsor_account {
+			Some(sponsor_account) => v.with_sponsored_balance_for(&source_account, sponsor_account),
+			None => v.with_balance_for(&source_account),
+		})
+		.and_then(|v| v.with_gas_quota(Pallet::<T>::gas_quota_remaining(&source)))
 		.map_err(|error| RunnerError { error, weight })?;
 		Ok(())
 	}
+}
+
+impl<T: Config> RunnerT<T> for Runner<T>
+where
+	BalanceOf<T>: TryFrom<U256> + Into<U256>,
+{
+	type Error = Error<T>;
+
+	fn validate(
+		source: H160,
+		target: Option<H160>,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		evm_config: &evm::Config,
+	) -> Result<(), RunnerError<Self::Error>> {
+		// Calls and creates check the sender code in execution, so `validate_inner` leaves it out.
+		if is_transactional && !evm_config.estimate {
+			Self::ensure_source_has_no_code(source, Weight::zero())?;
+		}
+		let evm_config = Pallet::<T>::config_with_code_limits(evm_config);
+		Self::validate_inner(
+			source,
+			None,
+			target,
+			input,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			access_list,
+			is_transactional,
+			weight_limit,
+			proof_size_base_cost,
+			&evm_config,
+		)
+	}
 
 	fn call(
 		source: H160,
@@ -472,9 +639,11 @@ where
 		proof_size_base_cost: Option<u64>,
 		config: &evm::Config,
 	) -> Result<CallInfo, RunnerError<Self::Error>> {
+		let config = Pallet::<T>::config_with_code_limits(config);
 		if validate {
-			Self::validate(
+			Self::validate_inner(
 				source,
+				None,
 				Some(target),
 				input.clone(),
 				value,
@@ -486,17 +655,73 @@ where
 				is_transactional,
 				weight_limit,
 				proof_size_base_cost,
-				config,
+				&config,
 			)?;
 		}
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = PausablePrecompiles::<T>::new(T::PrecompilesValue::get());
 		Self::execute(
 			source,
+			None,
+			Some(target),
 			value,
 			gas_limit,
 			max_fee_per_gas,
 			max_priority_fee_per_gas,
-			config,
+			&config,
+			&precompiles,
+			is_transactional,
+			weight_limit,
+			proof_size_base_cost,
+			|executor| executor.transact_call(source, target, value, input, gas_limit, access_list),
+		)
+	}
+
+	fn call_sponsored(
+		sponsor: H160,
+		source: H160,
+		target: H160,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		is_transactional: bool,
+		validate: bool,
+		weight_limit: Option<Weight>,
+		proof_size_base_cost: Option<u64>,
+		config: &evm::Config,
+	) -> Result<CallInfo, RunnerError<Self::Error>> {
+		let config = Pallet::<T>::config_with_code_limits(config);
+		if validate {
+			Self::validate_inner(
+				source,
+				Some(sponsor),
+				Some(target),
+				input.clone(),
+				value,
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list.clone(),
+				is_transactional,
+				weight_limit,
+				proof_size_base_cost,
+				&config,
+			)?;
+		}
+		let precompiles = PausablePrecompiles::<T>::new(T::PrecompilesValue::get());
+		Self::execute(
+			source,
+			Some(sponsor),
+			Some(target),
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			&config,
 			&precompiles,
 			is_transactional,
 			weight_limit,
@@ -520,10 +745,12 @@ where
 		proof_size_base_cost: Option<u64>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, RunnerError<Self::Error>> {
+		let config = Pallet::<T>::config_with_code_limits(config);
 		if validate {
-			Self::validate(
+			Self::validate_inner(
 				source,
 				None,
+				None,
 				init.clone(),
 				value,
 				gas_limit,
@@ -534,27 +761,46 @@ where
 				is_transactional,
 				weight_limit,
 				proof_size_base_cost,
-				config,
+				&config,
 			)?;
 		}
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = PausablePrecompiles::<T>::new(T::PrecompilesValue::get());
 		Self::execute(
 			source,
+			None,
+			None,
 			value,
 			gas_limit,
 			max_fee_per_gas,
 			max_priority_fee_per_gas,
-			config,
+			&config,
 			&precompiles,
 			is_transactional,
 			weight_limit,
 			proof_size_base_cost,
 			|executor| {
-				let address = executor.create_address(evm::CreateScheme::Legacy { caller: source });
-				T::OnCreate::on_create(source, address);
-				let (reason, _) =
-					executor.transact_create(source, value, init, gas_limit, access_list);
-				(reason, address)
+				let scheme = evm::CreateScheme::Legacy { caller: source };
+				match T::CreateAddressScheme::create_address(scheme) {
+					Some(address) => {
+						T::OnCreate::on_create(source, address);
+						let (reason, _) = executor.transact_create_force_address(
+							source,
+							value,
+							init,
+							gas_limit,
+							access_list,
+							address,
+						);
+						(reason, address)
+					}
+					None => {
+						let address = executor.create_address(scheme);
+						T::OnCreate::on_create(source, address);
+						let (reason, _) =
+							executor.transact_create(source, value, init, gas_limit, access_list);
+						(reason, address)
+					}
+				}
 			},
 		)
 	}
@@ -575,10 +821,12 @@ where
 		proof_size_base_cost: Option<u64>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, RunnerError<Self::Error>> {
+		let config = Pallet::<T>::config_with_code_limits(config);
 		if validate {
-			Self::validate(
+			Self::validate_inner(
 				source,
 				None,
+				None,
 				init.clone(),
 				value,
 				gas_limit,
@@ -589,45 +837,95 @@ where
 				is_transactional,
 				weight_limit,
 				proof_size_base_cost,
-				config,
+				&config,
 			)?;
 		}
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = PausablePrecompiles::<T>::new(T::PrecompilesValue::get());
 		let code_hash = H256::from(sp_io::hashing::keccak_256(&init));
 		Self::execute(
 			source,
+			None,
+			None,
 			value,
 			gas_limit,
 			max_fee_per_gas,
 			max_priority_fee_per_gas,
-			config,
+			&config,
 			&precompiles,
 			is_transactional,
 			weight_limit,
 			proof_size_base_cost,
 			|executor| {
-				let address = executor.create_address(evm::CreateScheme::Create2 {
+				let scheme = evm::CreateScheme::Create2 {
 					caller: source,
 					code_hash,
 					salt,
-				});
-				T::OnCreate::on_create(source, address);
-				let (reason, _) =
-					executor.transact_create2(source, value, init, salt, gas_limit, access_list);
-				(reason, address)
+				};
+				match T::CreateAddressScheme::create_address(scheme) {
+					Some(address) => {
+						T::OnCreate::on_create(source, address);
+						let (reason, _) = executor.transact_create_force_address(
+							source,
+							value,
+							init,
+							gas_limit,
+							access_list,
+							address,
+						);
+						(reason, address)
+					}
+					None => {
+						let address = executor.create_address(scheme);
+						T::OnCreate::on_create(source, address);
+						let (reason, _) = executor.transact_create2(
+							source,
+							value,
+							init,
+							salt,
+							gas_limit,
+							access_list,
+						);
+						(reason, address)
+					}
+				}
 			},
 		)
 	}
 }
 
+/// Journaled substate of the call frames.
+///
+/// Logs and deletions of all the frames are kept in flat collections, each entered frame
+/// recording where its own entries start so that they can be dropped when it is reverted. Exiting
+/// a frame therefore never moves or merges the entries of the frames it returns to.
 struct SubstrateStackSubstate<'config> {
 	metadata: StackSubstateMetadata<'config>,
 	deletes: BTreeSet<H160>,
 	logs: Vec<Log>,
-	parent: Option<Box<SubstrateStackSubstate<'config>>>,
+	/// Addresses inserted into `deletes` while frames are entered, in insertion order.
+	deletes_journal: Vec<H160>,
+	/// Parents of the current frame, innermost last.
+	frames: Vec<SubstrateStackFrame<'config>>,
+}
+
+/// Checkpoint of the parent of an entered frame.
+struct SubstrateStackFrame<'config> {
+	metadata: StackSubstateMetadata<'config>,
+	logs_len: usize,
+	deletes_journal_len: usize,
 }
 
 impl<'config> SubstrateStackSubstate<'config> {
+	pub fn new(metadata: StackSubstateMetadata<'config>) -> Self {
+		Self {
+			metadata,
+			deletes: BTreeSet::new(),
+			logs: Vec::new(),
+			deletes_journal: Vec::new(),
+			frames: Vec::new(),
+		}
+	}
+
 	pub fn metadata(&self) -> &StackSubstateMetadata<'config> {
 		&self.metadata
 	}
@@ -637,63 +935,66 @@ impl<'config> SubstrateStackSubstate<'config> {
 	}
 
 	pub fn enter(&mut self, gas_limit: u64, is_static: bool) {
-		let mut entering = Self {
-			metadata: self.metadata.spit_child(gas_limit, is_static),
-			parent: None,
-			deletes: BTreeSet::new(),
-			logs: Vec::new(),
-		};
-		mem::swap(&mut entering, self);
-
-		self.parent = Some(Box::new(entering));
+		let entering = self.metadata.spit_child(gas_limit, is_static);
+		self.frames.push(SubstrateStackFrame {
+			metadata: mem::replace(&mut self.metadata, entering),
+			logs_len: self.logs.len(),
+			deletes_journal_len: self.deletes_journal.len(),
+		});
 
 		sp_io::storage::start_transaction();
 	}
 
 	pub fn exit_commit(&mut self) -> Result<(), ExitError> {
-		let mut exited = *self.parent.take().expect("Cannot commit on root substate");
-		mem::swap(&mut exited, self);
+		let frame = self.frames.pop().expect("Cannot commit on root substate");
+		let exited = mem::replace(&mut self.metadata, frame.metadata);
+		self.metadata.swallow_commit(exited)?;
 
-		self.metadata.swallow_commit(exited.metadata)?;
-		self.logs.append(&mut exited.logs);
-		self.deletes.append(&mut exited.deletes);
+		// Deletions only need to be journaled while they can still be reverted.
+		if self.frames.is_empty() {
+			self.deletes_journal.clear();
+		}
 
 		sp_io::storage::commit_transaction();
 		Ok(())
 	}
 
 	pub fn exit_revert(&mut self) -> Result<(), ExitError> {
-		let mut exited = *self.parent.take().expect("Cannot discard on root substate");
-		mem::swap(&mut exited, self);
-		self.metadata.swallow_revert(exited.metadata)?;
+		let frame = self.frames.pop().expect("Cannot discard on root substate");
+		self.rollback(frame.logs_len, frame.deletes_journal_len);
+		let exited = mem::replace(&mut self.metadata, frame.metadata);
+		self.metadata.swallow_revert(exited)?;
 
 		sp_io::storage::rollback_transaction();
 		Ok(())
 	}
 
 	pub fn exit_discard(&mut self) -> Result<(), ExitError> {
-		let mut exited = *self.parent.take().expect("Cannot discard on root substate");
-		mem::swap(&mut exited, self);
-		self.metadata.swallow_discard(exited.metadata)?;
+		let frame = self.frames.pop().expect("Cannot discard on root substate");
+		self.rollback(frame.logs_len, frame.deletes_journal_len);
+		let exited = mem::replace(&mut self.metadata, frame.metadata);
+		self.metadata.swallow_discard(exited)?;
 
 		sp_io::storage::rollback_transaction();
 		Ok(())
 	}
 
-	pub fn deleted(&self, address: H160) -> bool {
-		if self.deletes.contains(&address) {
-			return true;
-		}
-
-		if let Some(parent) = self.parent.as_ref() {
-			return parent.deleted(address);
+	/// Drop the logs and deletions recorded after the given checkpoint.
+	fn rollback(&mut self, logs_len: usize, deletes_journal_len: usize) {
+		self.logs.truncate(logs_len);
+		for address in self.deletes_journal.drain(deletes_journal_len..) {
+			self.deletes.remove(&address);
 		}
+	}
 
-		false
+	pub fn deleted(&self, address: H160) -> bool {
+		self.deletes.contains(&address)
 	}
 
 	pub fn set_deleted(&mut self, address: H160) {
-		self.deletes.insert(address);
+		if self.deletes.insert(address) && !self.frames.is_empty() {
+			self.deletes_journal.push(address);
+		}
 	}
 
 	pub fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
@@ -704,16 +1005,10 @@ impl<'config> SubstrateStackSubstate<'config> {
 		});
 	}
 
-	fn recursive_is_cold<F: Fn(&Accessed) -> bool>(&self, f: &F) -> bool {
-		let local_is_accessed = self.metadata.accessed().as_ref().map(f).unwrap_or(false);
-		if local_is_accessed {
-			false
-		} else {
-			self.parent
-				.as_ref()
-				.map(|p| p.recursive_is_cold(f))
-				.unwrap_or(true)
-		}
+	fn is_cold<F: Fn(&Accessed) -> bool>(&self, f: &F) -> bool {
+		!core::iter::once(&self.metadata)
+			.chain(self.frames.iter().rev().map(|frame| &frame.metadata))
+			.any(|metadata| metadata.accessed().as_ref().map(f).unwrap_or(false))
 	}
 }
 
@@ -745,12 +1040,7 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 		let storage_meter = storage_limit.map(StorageMeter::new);
 		Self {
 			vicinity,
-			substate: SubstrateStackSubstate {
-				metadata,
-				deletes: BTreeSet::new(),
-				logs: Vec::new(),
-				parent: None,
-			},
+			substate: SubstrateStackSubstate::new(metadata),
 			_marker: PhantomData,
 			original_storage: BTreeMap::new(),
 			recorded: Default::default(),
@@ -772,6 +1062,52 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 	}
 }
 
+impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T>
+where
+	BalanceOf<T>: TryFrom<U256> + Into<U256>,
+{
+	/// Hold the storage deposit of the new storage slot `index` of `address` from the transaction
+	/// origin, or release the deposit of the cleared slot to the account holding it.
+	fn record_storage_deposit(
+		&mut self,
+		address: H160,
+		index: H256,
+		current: H256,
+		new: H256,
+	) -> Result<(), ExitError> {
+		let deposit = T::StorageDepositPerSlot::get();
+		let created = current.is_zero() && !new.is_zero();
+		let cleared = !current.is_zero() && new.is_zero();
+		if deposit.is_zero() || !(created || cleared) {
+			return Ok(());
+		}
+
+		// The `StorageDeposits` entry and the balance of the depositor are read and written.
+		self.record_external_cost(
+			Some(T::DbWeight::get().reads_writes(2, 2).ref_time()),
+			Some(STORAGE_DEPOSIT_PROOF_SIZE.saturating_add(ACCOUNT_BASIC_PROOF_SIZE)),
+			created.then_some(STORAGE_DEPOSIT_PROOF_SIZE),
+		)?;
+
+		if created {
+			let origin = T::AddressMapping::into_account_id(self.vicinity.origin);
+			let deposit: DepositBalanceOf<T> =
+				UniqueSaturatedInto::<u128>::unique_saturated_into(deposit).unique_saturated_into();
+			T::Currency::hold(
+				&T::RuntimeHoldReason::from(HoldReason::StorageDeposit),
+				&origin,
+				deposit,
+			)
+			.map_err(|_| ExitError::OutOfFund)?;
+			<StorageDeposits<T>>::insert(address, index, (origin, deposit));
+		} else {
+			Pallet::<T>::release_storage_deposit(address, index);
+		}
+
+		Ok(())
+	}
+}
+
 impl<'vicinity, 'config, T: Config> BackendT for SubstrateStackState<'vicinity, 'config, T>
 where
 	BalanceOf<T>: TryFrom<U256> + Into<U256>,
@@ -934,8 +1270,7 @@ where
 	}
 
 	fn reset_storage(&mut self, address: H160) {
-		#[allow(deprecated)]
-		let _ = <AccountStorages<T>>::remove_prefix(address, None);
+		let _ = Pallet::<T>::clear_account_storages(address, u32::MAX);
 	}
 
 	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
@@ -989,12 +1324,12 @@ where
 
 	fn is_cold(&self, address: H160) -> bool {
 		self.substate
-			.recursive_is_cold(&|a| a.accessed_addresses.contains(&address))
+			.is_cold(&|a| a.accessed_addresses.contains(&address))
 	}
 
 	fn is_storage_cold(&self, address: H160, key: H256) -> bool {
 		self.substate
-			.recursive_is_cold(&|a: &Accessed| a.accessed_storage.contains(&(address, key)))
+			.is_cold(&|a: &Accessed| a.accessed_storage.contains(&(address, key)))
 	}
 
 	fn code_size(&self, address: H160) -> U256 {
@@ -1079,6 +1414,12 @@ where
 		gas_cost: GasCost,
 		target: evm::gasometer::StorageTarget,
 	) -> Result<(), ExitError> {
+		if let (GasCost::SStore { current, new, .. }, StorageTarget::Slot(address, index)) =
+			(gas_cost, target)
+		{
+			self.record_storage_deposit(address, index, current, new)?;
+		}
+
 		if let Some(storage_meter) = self.storage_meter.as_mut() {
 			storage_meter
 				.record_dynamic_opcode_cost(opcode, gas_cost, target)
@@ -1277,24 +1618,28 @@ mod tests {
 		// Should fail with the appropriate error if there is reentrancy
 		let res = Runner::<Test>::execute(
 			H160::default(),
+			None,
+			None,
 			U256::default(),
 			100_000,
 			None,
 			None,
 			&config,
-			&MockPrecompileSet,
+			&PausablePrecompiles::new(MockPrecompileSet),
 			false,
 			None,
 			None,
 			|_| {
 				let res = Runner::<Test>::execute(
 					H160::default(),
+					None,
+					None,
 					U256::default(),
 					100_000,
 					None,
 					None,
 					&config,
-					&MockPrecompileSet,
+					&PausablePrecompiles::new(MockPrecompileSet),
 					false,
 					None,
 					None,
@@ -1321,12 +1666,14 @@ mod tests {
 		// Should succeed if there is no reentrancy
 		let res = Runner::<Test>::execute(
 			H160::default(),
+			None,
+			None,
 			U256::default(),
 			100_000,
 			None,
 			None,
 			&config,
-			&MockPrecompileSet,
+			&PausablePrecompiles::new(MockPrecompileSet),
 			false,
 			None,
 			None,
@@ -18,8 +18,10 @@ use super::*;
 use crate::mock::*;
 
 use frame_support::{
-	assert_ok,
-	traits::{LockIdentifier, LockableCurrency, WithdrawReasons},
+	assert_noop, assert_ok, parameter_types,
+	traits::{
+		fungible::InspectHold, fungibles, Hooks, LockIdentifier, LockableCurrency, WithdrawReasons,
+	},
 };
 use sp_runtime::BuildStorage;
 use std::{collections::BTreeMap, str::FromStr};
@@ -1175,6 +1177,286 @@ fn refunds_and_priority_should_work() {
 	});
 }
 
+const FEE_ASSET: u32 = 1;
+
+parameter_types! {
+	pub const FeeAssetId: u32 = FEE_ASSET;
+}
+
+/// Converts native fees into one unit of the fee asset per three native units, rounding up.
+pub struct OneThirdFee;
+impl FeeAssetConversion<u64> for OneThirdFee {
+	fn to_asset_balance(fee: U256) -> Option<u64> {
+		(fee.checked_add(U256::from(2))? / 3).try_into().ok()
+	}
+}
+
+type FeeAssetAdapter = EVMFungiblesAdapter<Assets, FeeAssetId, OneThirdFee, ()>;
+
+/// Create the fee asset and mint `balance` of it to `who`.
+fn fund_fee_asset(who: &H160, balance: u64) -> AccountIdOf<Test> {
+	let account_id = <Test as Config>::AddressMapping::into_account_id(*who);
+	assert_ok!(<Assets as fungibles::Create<_>>::create(
+		FEE_ASSET,
+		account_id.clone(),
+		true,
+		1
+	));
+	assert_ok!(<Assets as fungibles::Mutate<_>>::mint_into(
+		FEE_ASSET,
+		&account_id,
+		balance
+	));
+	account_id
+}
+
+fn fee_asset_balance(account_id: &AccountIdOf<Test>) -> u64 {
+	<Assets as fungibles::Inspect<_>>::balance(FEE_ASSET, account_id)
+}
+
+#[test]
+fn fungibles_adapter_withdraws_converted_fee() {
+	new_test_ext().execute_with(|| {
+		let who = H160::from_low_u64_be(0x1000);
+		let account_id = fund_fee_asset(&who, 1_000);
+
+		let paid =
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::from(300))
+				.expect("fee is withdrawn");
+		assert_eq!(paid.as_ref().map(|credit| credit.peek()), Some(100));
+		assert_eq!(fee_asset_balance(&account_id), 900);
+
+		// Conversions round up in favor of the fee receiver.
+		let paid =
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::from(301))
+				.expect("fee is withdrawn");
+		assert_eq!(paid.as_ref().map(|credit| credit.peek()), Some(101));
+		assert_eq!(fee_asset_balance(&account_id), 799);
+
+		let paid =
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::zero())
+				.expect("fee is withdrawn");
+		assert!(paid.is_none());
+		assert_eq!(fee_asset_balance(&account_id), 799);
+	});
+}
+
+#[test]
+fn fungibles_adapter_refunds_unused_fee() {
+	new_test_ext().execute_with(|| {
+		let who = H160::from_low_u64_be(0x1000);
+		let account_id = fund_fee_asset(&who, 1_000);
+		let author_id = <Test as Config>::AddressMapping::into_account_id(EVM::find_author());
+
+		let paid =
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::from(300))
+				.expect("fee is withdrawn");
+		// The corrected fee of 151 converts to 51, the base fee of 90 to 30.
+		let tip = <FeeAssetAdapter as OnChargeEVMTransaction<Test>>::correct_and_deposit_fee(
+			&who,
+			U256::from(151),
+			U256::from(90),
+			paid,
+		);
+		assert_eq!(fee_asset_balance(&account_id), 949);
+		assert_eq!(tip.as_ref().map(|credit| credit.peek()), Some(21));
+
+		<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::pay_priority_fee(tip);
+		assert_eq!(fee_asset_balance(&author_id), 21);
+		// The base fee is handed to the unbalance handler, burning it.
+		assert_eq!(
+			<Assets as fungibles::Inspect<_>>::total_issuance(FEE_ASSET),
+			949 + 21
+		);
+	});
+}
+
+#[test]
+fn fungibles_adapter_fails_without_enough_asset() {
+	new_test_ext().execute_with(|| {
+		let who = H160::from_low_u64_be(0x1000);
+		let account_id = fund_fee_asset(&who, 50);
+
+		assert!(matches!(
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::from(300)),
+			Err(Error::<Test>::BalanceLow)
+		));
+		assert!(matches!(
+			<FeeAssetAdapter as OnChargeEVMTransaction<Test>>::withdraw_fee(&who, U256::MAX),
+			Err(Error::<Test>::FeeOverflow)
+		));
+		assert_eq!(fee_asset_balance(&account_id), 50);
+	});
+}
+
+#[test]
+fn sponsored_call_charges_sponsor() {
+	new_test_ext().execute_with(|| {
+		let sponsor = H160::default();
+		let sender = H160::from_low_u64_be(0x5000);
+		let sponsored_call = || {
+			EVM::sponsored_call(
+				RuntimeOrigin::root(),
+				sender,
+				sponsor,
+				H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				U256::from(2_000_000_000),
+				None,
+				None,
+				Vec::new(),
+			)
+		};
+		let (base_fee, _) = <Test as Config>::FeeCalculator::min_gas_price();
+		let allowance = base_fee * 1000000;
+
+		assert_eq!(
+			sponsored_call().unwrap_err().error,
+			Error::<Test>::SponsorAllowanceTooLow.into()
+		);
+
+		assert_ok!(EVM::set_sponsor_allowance(
+			RuntimeOrigin::root(),
+			sponsor,
+			sender,
+			allowance
+		));
+		let before_call = EVM::account_basic(&sponsor).0.balance;
+		assert_ok!(sponsored_call());
+
+		let fee = U256::from(21_000) * base_fee;
+		assert_eq!(EVM::account_basic(&sponsor).0.balance, before_call - fee);
+		assert_eq!(EVM::account_basic(&sender).0.balance, U256::zero());
+		assert_eq!(EVM::account_basic(&sender).0.nonce, U256::one());
+		assert_eq!(
+			SponsorAllowances::<Test>::get(sponsor, sender),
+			allowance - fee
+		);
+
+		// The remaining allowance no longer covers the fee.
+		assert_eq!(
+			sponsored_call().unwrap_err().error,
+			Error::<Test>::SponsorAllowanceTooLow.into()
+		);
+	});
+}
+
+#[test]
+fn gas_quota_limits_transactional_calls() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		GasQuota::set(50_000);
+		let sender = H160::default();
+		let call = |gas_limit| {
+			EVM::call(
+				RuntimeOrigin::root(),
+				sender,
+				H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+				Vec::new(),
+				U256::zero(),
+				gas_limit,
+				U256::from(2_000_000_000),
+				None,
+				None,
+				Vec::new(),
+			)
+		};
+
+		assert_eq!(EVM::gas_quota_remaining(&sender), Some(U256::from(50_000)));
+		assert_ok!(call(30_000));
+		assert_eq!(EVM::gas_quota_remaining(&sender), Some(U256::from(29_000)));
+		assert_eq!(
+			call(30_000).unwrap_err().error,
+			Error::<Test>::GasQuotaExceeded.into()
+		);
+		assert_ok!(call(29_000));
+		assert_eq!(EVM::gas_quota_remaining(&sender), Some(U256::from(8_000)));
+
+		// The quota is refilled at the start of the next period.
+		System::set_block_number(10);
+		assert_eq!(EVM::gas_quota_remaining(&sender), Some(U256::from(50_000)));
+		assert_ok!(call(30_000));
+
+		GasQuota::set(0);
+		assert_eq!(EVM::gas_quota_remaining(&sender), None);
+	});
+}
+
+#[test]
+fn gas_quota_usage_is_metered() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let proof_size_usage = || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+				Vec::new(),
+				U256::zero(),
+				30_000,
+				Some(U256::from(2_000_000_000)),
+				None,
+				None,
+				Vec::new(),
+				true, // transactional
+				true, // must be validated
+				Some(Weight::from_parts(u64::MAX, u64::MAX)),
+				Some(0),
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+			.weight_info
+			.and_then(|weight_info| weight_info.proof_size_usage)
+			.expect("proof size is recorded")
+		};
+
+		let without_quota = proof_size_usage();
+		GasQuota::set(50_000);
+		assert_eq!(
+			proof_size_usage(),
+			without_quota
+				+ fp_evm::GAS_QUOTA_USAGE_PROOF_SIZE
+				+ fp_evm::GAS_QUOTA_PERIOD_PROOF_SIZE
+		);
+		GasQuota::set(0);
+	});
+}
+
+#[test]
+fn gas_quota_usage_of_past_periods_is_cleared_on_idle() {
+	new_test_ext().execute_with(|| {
+		GasQuota::set(50_000);
+		let sender = H160::default();
+		let other = H160::from_low_u64_be(0x1000);
+
+		System::set_block_number(1);
+		EVM::record_gas_quota_usage(&sender, 21_000);
+		EVM::record_gas_quota_usage(&other, 21_000);
+		System::set_block_number(10);
+		EVM::record_gas_quota_usage(&sender, 21_000);
+		assert_eq!(GasQuotaUsage::<Test>::iter_prefix(0).count(), 2);
+		assert!(GasQuotaPeriods::<Test>::contains_key(0));
+
+		// Not enough weight to do any work.
+		EVM::on_idle(10, Weight::zero());
+		assert_eq!(GasQuotaUsage::<Test>::iter_prefix(0).count(), 2);
+
+		// Only the entries of the past periods are removed.
+		EVM::on_idle(10, Weight::MAX);
+		assert_eq!(GasQuotaUsage::<Test>::iter_prefix(0).count(), 0);
+		assert!(!GasQuotaPeriods::<Test>::contains_key(0));
+		assert_eq!(GasQuotaUsage::<Test>::get(1, sender), 21_000);
+		assert_eq!(EVM::gas_quota_remaining(&sender), Some(U256::from(29_000)));
+
+		// All the entries are removed once gas quotas are disabled.
+		GasQuota::set(0);
+		EVM::on_idle(10, Weight::MAX);
+		assert_eq!(GasQuotaUsage::<Test>::iter().count(), 0);
+		assert_eq!(GasQuotaPeriods::<Test>::iter().count(), 0);
+	});
+}
+
 #[test]
 fn call_should_fail_with_priority_greater_than_max_fee() {
 	new_test_ext().execute_with(|| {
@@ -1434,38 +1716,1390 @@ fn eip3607_transaction_from_contract() {
 }
 
 #[test]
-fn metadata_code_gets_cached() {
+fn eip3607_validation_rejects_sender_with_code() {
 	new_test_ext().execute_with(|| {
-		let address = H160::repeat_byte(0xaa);
+		let contract = H160::from_str("1000000000000000000000000000000000000001").unwrap();
 
-		crate::Pallet::<Test>::create_account(address, b"Exemple".to_vec());
+		// external transaction
+		match <Test as Config>::Runner::validate(
+			contract,
+			Some(H160::default()),
+			Vec::new(),
+			U256::zero(),
+			1000000,
+			Some(FixedGasPrice::min_gas_price().0),
+			None,
+			None,
+			Vec::new(),
+			true, // transactional
+			None,
+			None,
+			<Test as Config>::config(),
+		) {
+			Err(RunnerError {
+				error: Error::TransactionMustComeFromEOA,
+				..
+			}) => (),
+			_ => panic!("Should have failed"),
+		}
 
-		let metadata = crate::Pallet::<Test>::account_code_metadata(address);
-		assert_eq!(metadata.size, 7);
+		// internal call
+		assert!(<Test as Config>::Runner::validate(
+			contract,
+			Some(H160::default()),
+			Vec::new(),
+			U256::zero(),
+			1000000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false, // non-transactional
+			None,
+			None,
+			<Test as Config>::config(),
+		)
+		.is_ok());
+	});
+}
+
+#[test]
+fn access_list_gas_accounting() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::repeat_byte(0xcc);
+		// PUSH1 0x00 SLOAD POP STOP
+		crate::Pallet::<Test>::create_account(contract, hex::decode("6000545000").unwrap());
+
+		let used_gas = |access_list: Vec<(H160, Vec<H256>)>| {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				access_list,
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call succeeds")
+			.used_gas
+			.standard
+		};
+
+		// 21_000 base cost + 3 (PUSH1) + 2_100 (cold SLOAD) + 2 (POP).
+		let without_access_list = used_gas(Vec::new());
+		assert_eq!(without_access_list, U256::from(23_105u64));
+
+		// 2_400 per address + 1_900 per storage key, the SLOAD is then warm (100).
+		let with_warm_slot = used_gas(vec![(contract, vec![H256::zero()])]);
+		assert_eq!(with_warm_slot, U256::from(25_405u64));
+
+		// Unrelated entries only pay the intrinsic cost.
+		let with_unrelated_address = used_gas(vec![(H160::repeat_byte(0xdd), Vec::new())]);
 		assert_eq!(
-			metadata.hash,
-			hex_literal::hex!("e8396a990fe08f2402e64a00647e41dadf360ba078a59ba79f55e876e67ed4bc")
-				.into()
+			with_unrelated_address,
+			without_access_list + U256::from(2_400u64)
 		);
+	});
+}
 
-		let metadata2 = <AccountCodesMetadata<Test>>::get(address).expect("to have metadata set");
-		assert_eq!(metadata, metadata2);
+#[test]
+fn shanghai_config_enables_push0() {
+	new_test_ext().execute_with(|| {
+		let create = |config: &evm::Config| {
+			<Test as Config>::Runner::create(
+				H160::default(),
+				// PUSH0 PUSH0 RETURN
+				hex::decode("5f5ff3").unwrap(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				config,
+			)
+			.expect("create executes")
+			.exit_reason
+		};
+
+		assert_eq!(
+			create(&LONDON_CONFIG),
+			ExitReason::Error(ExitError::InvalidCode(evm::Opcode::PUSH0))
+		);
+		assert!(matches!(create(&SHANGHAI_CONFIG), ExitReason::Succeed(_)));
 	});
 }
 
 #[test]
-fn metadata_empty_dont_code_gets_cached() {
+fn shanghai_config_charges_init_code_per_word() {
 	new_test_ext().execute_with(|| {
-		let address = H160::repeat_byte(0xaa);
+		let used_gas = |config: &evm::Config| {
+			<Test as Config>::Runner::create(
+				H160::default(),
+				// PUSH1 0x00 PUSH1 0x00 RETURN
+				hex::decode("60006000f3").unwrap(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				config,
+			)
+			.expect("create executes")
+			.used_gas
+			.standard
+		};
 
-		let metadata = crate::Pallet::<Test>::account_code_metadata(address);
-		assert_eq!(metadata.size, 0);
+		// One word of init code is charged 2 gas.
 		assert_eq!(
-			metadata.hash,
-			hex_literal::hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
-				.into()
+			used_gas(&SHANGHAI_CONFIG),
+			used_gas(&LONDON_CONFIG) + U256::from(2u64)
 		);
+	});
+}
 
-		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
+#[test]
+fn shanghai_config_limits_init_code_size() {
+	new_test_ext().execute_with(|| {
+		let res = <Test as Config>::Runner::create(
+			H160::default(),
+			vec![0u8; 49_153],
+			U256::zero(),
+			1000000,
+			Some(FixedGasPrice::min_gas_price().0),
+			None,
+			None,
+			Vec::new(),
+			true, // transactional
+			true, // must be validated
+			None,
+			None,
+			&SHANGHAI_CONFIG,
+		);
+		assert!(matches!(
+			res,
+			Err(RunnerError {
+				error: Error::MaxInitCodeSizeExceeded,
+				..
+			})
+		));
+	});
+}
+
+#[test]
+fn set_hardfork_switches_config() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(EVM::hardfork(), Hardfork::Shanghai);
+		assert!(<Test as Config>::config().has_push0);
+
+		assert_noop!(
+			EVM::set_hardfork(RuntimeOrigin::none(), Hardfork::London),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(EVM::set_hardfork(RuntimeOrigin::root(), Hardfork::London));
+		assert_eq!(EVM::hardfork(), Hardfork::London);
+		assert!(!<Test as Config>::config().has_push0);
+		System::assert_last_event(RuntimeEvent::EVM(Event::HardforkActivated {
+			hardfork: Hardfork::London,
+		}));
+	});
+}
+
+#[test]
+fn scheduled_hardfork_activates_at_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			EVM::schedule_hardfork(RuntimeOrigin::root(), Hardfork::London, 1),
+			Error::<Test>::HardforkActivationInPast
+		);
+
+		assert_ok!(EVM::schedule_hardfork(
+			RuntimeOrigin::root(),
+			Hardfork::London,
+			3
+		));
+		System::assert_last_event(RuntimeEvent::EVM(Event::HardforkScheduled {
+			hardfork: Hardfork::London,
+			activation: 3,
+		}));
+
+		System::set_block_number(2);
+		EVM::on_initialize(2);
+		assert_eq!(EVM::hardfork(), Hardfork::Shanghai);
+
+		System::set_block_number(3);
+		EVM::on_initialize(3);
+		assert_eq!(EVM::hardfork(), Hardfork::London);
+		assert!(<ScheduledHardfork<Test>>::get().is_none());
+		System::assert_last_event(RuntimeEvent::EVM(Event::HardforkActivated {
+			hardfork: Hardfork::London,
+		}));
+	});
+}
+
+#[test]
+fn max_code_size_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let create = || {
+			<Test as Config>::Runner::create(
+				H160::default(),
+				// PUSH1 0x03 PUSH1 0x00 RETURN, deploys 3 bytes of code.
+				hex::decode("60036000f3").unwrap(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("create executes")
+			.exit_reason
+		};
+
+		MaxCodeSize::set(3);
+		assert!(matches!(create(), ExitReason::Succeed(_)));
+
+		MaxCodeSize::set(2);
+		assert_eq!(create(), ExitReason::Error(ExitError::CreateContractLimit));
+	});
+}
+
+#[test]
+fn max_init_code_size_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let create = || {
+			<Test as Config>::Runner::create(
+				H160::default(),
+				// PUSH1 0x00 PUSH1 0x00 RETURN
+				hex::decode("60006000f3").unwrap(),
+				U256::zero(),
+				1000000,
+				Some(FixedGasPrice::min_gas_price().0),
+				None,
+				None,
+				Vec::new(),
+				true, // transactional
+				true, // must be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+		};
+
+		MaxInitCodeSize::set(5);
+		assert!(create().is_ok());
+
+		MaxInitCodeSize::set(4);
+		assert!(matches!(
+			create(),
+			Err(RunnerError {
+				error: Error::MaxInitCodeSizeExceeded,
+				..
+			})
+		));
+	});
+}
+
+#[test]
+fn config_with_code_limits_is_only_cloned_for_different_limits() {
+	new_test_ext().execute_with(|| {
+		let config = <Test as Config>::config();
+		assert!(matches!(
+			EVM::config_with_code_limits(config),
+			Cow::Borrowed(_)
+		));
+
+		MaxCodeSize::set(3);
+		let limited = EVM::config_with_code_limits(config);
+		assert!(matches!(limited, Cow::Owned(_)));
+		assert_eq!(limited.create_contract_limit, Some(3));
+		assert!(matches!(
+			EVM::config_with_code_limits(&limited),
+			Cow::Borrowed(_)
+		));
+	});
+}
+
+#[test]
+fn suicided_storage_is_cleared_on_idle() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let address = H160::from_low_u64_be(0x1000);
+		EVM::create_account(address, vec![0x00]);
+		for i in 0..3u64 {
+			<AccountStorages<Test>>::insert(
+				address,
+				H256::from_low_u64_be(i),
+				H256::repeat_byte(1),
+			);
+		}
+		EVM::remove_account(&address);
+		assert!(<Suicided<Test>>::contains_key(address));
+
+		// Disabled by default.
+		assert_eq!(EVM::clear_suicided_storage(Weight::MAX), Weight::zero());
+		assert_eq!(EVM::iter_account_storages(&address).count(), 3);
+
+		// Not enough weight to do any work.
+		SuicideIdleClearLimit::set(2);
+		assert_eq!(EVM::on_idle(1, Weight::zero()), Weight::zero());
+		assert_eq!(EVM::iter_account_storages(&address).count(), 3);
+
+		EVM::on_idle(1, Weight::MAX);
+		assert_eq!(EVM::iter_account_storages(&address).count(), 1);
+		assert!(<Suicided<Test>>::contains_key(address));
+		System::assert_last_event(RuntimeEvent::EVM(Event::SuicidedStorageCleared {
+			address,
+			entries: 2,
+			finished: false,
+		}));
+
+		System::set_block_number(2);
+		EVM::on_idle(2, Weight::MAX);
+		assert_eq!(EVM::iter_account_storages(&address).count(), 0);
+		assert!(!<Suicided<Test>>::contains_key(address));
+		System::assert_last_event(RuntimeEvent::EVM(Event::SuicidedStorageCleared {
+			address,
+			entries: 1,
+			finished: true,
+		}));
+	});
+}
+
+#[test]
+fn force_create_installs_contract() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let address = H160::from_low_u64_be(0x4200);
+		let code = hex::decode("600160005260206000f3").unwrap();
+		let storage = vec![
+			(H256::from_low_u64_be(0), H256::from_low_u64_be(42)),
+			(H256::from_low_u64_be(1), H256::zero()),
+		];
+
+		assert_noop!(
+			EVM::force_create(
+				RuntimeOrigin::none(),
+				address,
+				code.clone(),
+				storage.clone()
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EVM::force_create(RuntimeOrigin::root(), address, vec![], vec![]),
+			Error::<Test>::InvalidContractCode
+		);
+		assert_noop!(
+			EVM::force_create(
+				RuntimeOrigin::root(),
+				address,
+				vec![0; MaxCodeSize::get() as usize + 1],
+				vec![]
+			),
+			Error::<Test>::MaxCodeSizeExceeded
+		);
+		assert_noop!(
+			EVM::force_create(
+				RuntimeOrigin::root(),
+				H160::from_low_u64_be(1),
+				code.clone(),
+				vec![]
+			),
+			Error::<Test>::PrecompileAddress
+		);
+
+		assert_ok!(EVM::force_create(
+			RuntimeOrigin::root(),
+			address,
+			code.clone(),
+			storage
+		));
+		System::assert_last_event(RuntimeEvent::EVM(Event::Created { address }));
+		assert_eq!(<AccountCodes<Test>>::get(address), code);
+		assert_eq!(EVM::account_basic(&address).0.nonce, U256::one());
+		assert_eq!(
+			<AccountStorages<Test>>::get(address, H256::from_low_u64_be(0)),
+			H256::from_low_u64_be(42)
+		);
+		assert_eq!(EVM::iter_account_storages(&address).count(), 1);
+
+		assert_noop!(
+			EVM::force_create(RuntimeOrigin::root(), address, code, vec![]),
+			Error::<Test>::ContractAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn permissioned_create_and_call() {
+	new_test_ext().execute_with(|| {
+		let allowed = H160::from_low_u64_be(0x1000);
+		let denied = H160::from_low_u64_be(0x2000);
+		let create = |source, is_transactional| {
+			<Test as Config>::Runner::create(
+				source,
+				// PUSH1 0x00 PUSH1 0x00 RETURN
+				hex::decode("60006000f3").unwrap(),
+				U256::zero(),
+				1000000,
+				Some(U256::zero()),
+				None,
+				None,
+				Vec::new(),
+				is_transactional,
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.map(|info| info.exit_reason)
+		};
+		let call = |source, is_transactional| {
+			<Test as Config>::Runner::call(
+				source,
+				H160::default(),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				Some(U256::zero()),
+				None,
+				None,
+				Vec::new(),
+				is_transactional,
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.map(|info| info.exit_reason)
+		};
+
+		assert_ok!(EVM::set_create_allowed(
+			RuntimeOrigin::root(),
+			allowed,
+			true
+		));
+		assert_ok!(EVM::set_call_allowed(RuntimeOrigin::root(), allowed, true));
+		assert!(CreateAllowlist::<Test>::contains_key(allowed));
+		assert!(CallAllowlist::<Test>::contains_key(allowed));
+
+		Permissioned::set(true);
+		assert!(matches!(create(allowed, true), Ok(ExitReason::Succeed(_))));
+		assert!(matches!(
+			create(denied, true),
+			Err(RunnerError {
+				error: Error::CreateOriginNotAllowed,
+				..
+			})
+		));
+		assert!(matches!(call(allowed, true), Ok(ExitReason::Succeed(_))));
+		assert!(matches!(
+			call(denied, true),
+			Err(RunnerError {
+				error: Error::CallOriginNotAllowed,
+				..
+			})
+		));
+
+		// `eth_call` and `eth_estimateGas` aren't filtered.
+		assert!(matches!(create(denied, false), Ok(ExitReason::Succeed(_))));
+		assert!(matches!(call(denied, false), Ok(ExitReason::Succeed(_))));
+
+		assert_ok!(EVM::set_call_allowed(RuntimeOrigin::root(), allowed, false));
+		assert!(matches!(
+			call(allowed, true),
+			Err(RunnerError {
+				error: Error::CallOriginNotAllowed,
+				..
+			})
+		));
+		Permissioned::set(false);
+		assert!(matches!(call(denied, true), Ok(ExitReason::Succeed(_))));
+	});
+}
+
+/// RLP encoding of `[sender, nonce]` for single byte nonces, as hashed by `CREATE`.
+fn rlp_address_preimage(sender: H160, nonce: u64) -> Vec<u8> {
+	assert!(nonce < 0x80);
+	let mut preimage = vec![0xd6, 0x94];
+	preimage.extend_from_slice(sender.as_bytes());
+	preimage.push(if nonce == 0 { 0x80 } else { nonce as u8 });
+	preimage
+}
+
+#[test]
+fn namespaced_deployer_derives_create_addresses() {
+	new_test_ext().execute_with(|| {
+		let create = |source| {
+			<Test as Config>::Runner::create(
+				source,
+				// PUSH1 0x01 PUSH1 0x00 RETURN
+				hex::decode("60016000f3").unwrap(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("create succeeds")
+		};
+		let create2 = |source, salt| {
+			<Test as Config>::Runner::create2(
+				source,
+				hex::decode("60016000f3").unwrap(),
+				salt,
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("create2 succeeds")
+		};
+		type Scheme = NamespacedDeployer<SystemDeployer, ChainId>;
+		let deployer = SystemDeployer::get();
+		let user = H160::default();
+
+		let info = create(deployer);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(info.value, Scheme::derive(0, H256::zero()));
+		assert_eq!(AccountCodes::<Test>::get(info.value), vec![0x00]);
+		let info = create(deployer);
+		assert_eq!(info.value, Scheme::derive(0, H256::from_low_u64_be(1)));
+
+		let salt = H256::repeat_byte(0x11);
+		let code_hash = H256::from(sp_io::hashing::keccak_256(
+			&hex::decode("60016000f3").unwrap(),
+		));
+		let mut preimage = salt.as_bytes().to_vec();
+		preimage.extend_from_slice(code_hash.as_bytes());
+		let info = create2(deployer, salt);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(
+			info.value,
+			Scheme::derive(1, H256::from(sp_io::hashing::keccak_256(&preimage)))
+		);
+
+		// Other sources keep the standard derivation.
+		let (account, _) = EVM::account_basic(&user);
+		let info = create(user);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_ne!(info.value, Scheme::derive(0, H256::from_low_u64_be(1)));
+		assert_eq!(
+			info.value,
+			H160::from(H256::from(sp_io::hashing::keccak_256(
+				&rlp_address_preimage(user, account.nonce.low_u64())
+			)))
+		);
+	});
+}
+
+#[test]
+fn reverted_frames_drop_logs_and_deletions() {
+	new_test_ext().execute_with(|| {
+		let call = |target| {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				target,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+		};
+		// PUSH1 0x00 PUSH1 0x00 LOG0 PUSH1 0x00 PUSH1 0x00 REVERT
+		let logger = H160::from_low_u64_be(0x2000);
+		EVM::create_account(logger, hex::decode("60006000a060006000fd").unwrap());
+		// PUSH1 0x00 SELFDESTRUCT
+		let destructible = H160::from_low_u64_be(0x3000);
+		EVM::create_account(destructible, hex::decode("6000ff").unwrap());
+		// Calls `logger`, then logs:
+		// PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH2 0x2000 GAS CALL POP
+		// PUSH1 0x00 PUSH1 0x00 LOG0 STOP
+		let caller = H160::from_low_u64_be(0x1000);
+		EVM::create_account(
+			caller,
+			hex::decode("600060006000600060006120005af15060006000a000").unwrap(),
+		);
+		// Calls `destructible`, then reverts:
+		// PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH2 0x3000 GAS CALL POP
+		// PUSH1 0x00 PUSH1 0x00 REVERT
+		let reverter = H160::from_low_u64_be(0x4000);
+		EVM::create_account(
+			reverter,
+			hex::decode("600060006000600060006130005af15060006000fd").unwrap(),
+		);
+
+		let info = call(caller);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(
+			info.logs,
+			vec![Log {
+				address: caller,
+				topics: Vec::new(),
+				data: Vec::new(),
+			}]
+		);
+
+		let info = call(reverter);
+		assert!(matches!(info.exit_reason, ExitReason::Revert(_)));
+		assert!(!AccountCodes::<Test>::get(destructible).is_empty());
+
+		let info = call(destructible);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert!(AccountCodes::<Test>::get(destructible).is_empty());
+	});
+}
+
+#[test]
+fn paused_contract_calls_fail() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// PUSH1 0x00 PUSH1 0x00 RETURN
+		let callee = H160::from_low_u64_be(0x1000);
+		EVM::create_account(callee, hex::decode("60006000f3").unwrap());
+		// Forwards the call to `callee`:
+		// PUSH1 0x00 DUP1 DUP1 DUP1 DUP1 PUSH2 0x1000 GAS CALL PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+		let caller = H160::from_low_u64_be(0x2000);
+		EVM::create_account(
+			caller,
+			hex::decode("6000808080806110005af160005260206000f3").unwrap(),
+		);
+		let call = |target| {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				target,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+		};
+
+		assert_noop!(
+			EVM::pause_contract(RuntimeOrigin::none(), callee),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(EVM::pause_contract(RuntimeOrigin::root(), callee));
+		System::assert_last_event(RuntimeEvent::EVM(Event::ContractPaused { address: callee }));
+
+		let info = call(callee);
+		assert_eq!(info.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
+		assert_eq!(
+			info.value,
+			hex::decode(
+				"81990fd9\
+				 0000000000000000000000000000000000000000000000000000000000001000"
+			)
+			.unwrap()
+		);
+		// The nested call fails, `CALL` pushes 0.
+		let info = call(caller);
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(info.value, H256::zero().as_bytes().to_vec());
+
+		assert_ok!(EVM::unpause_contract(RuntimeOrigin::root(), callee));
+		assert!(matches!(call(callee).exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(
+			call(caller).value,
+			H256::from_low_u64_be(1).as_bytes().to_vec()
+		);
+	});
+}
+
+#[test]
+fn estimate_succeeds_with_value_above_balance() {
+	new_test_ext().execute_with(|| {
+		let source = H160::from_low_u64_be(0x3000);
+		let source_account = <Test as Config>::AddressMapping::into_account_id(source);
+		let _ = <Test as Config>::Currency::deposit_creating(&source_account, 100);
+		let target = H160::from_low_u64_be(0x1000);
+		let target_account = <Test as Config>::AddressMapping::into_account_id(target);
+		let call = |config: &evm::Config| {
+			<Test as Config>::Runner::call(
+				source,
+				target,
+				Vec::new(),
+				U256::from(1_000),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				None,
+				None,
+				config,
+			)
+			.expect("call executes")
+			.exit_reason
+		};
+
+		let mut config = <Test as Config>::config().clone();
+		config.estimate = true;
+		// The runner doesn't credit the source by itself.
+		assert_eq!(call(&config), ExitReason::Error(ExitError::OutOfFund));
+
+		let exit_reason = EVM::with_state_overrides(Vec::new(), || {
+			EVM::credit_estimate_balance(source, U256::from(1_000), 1000000, None);
+			call(&config)
+		});
+		assert!(matches!(exit_reason, ExitReason::Succeed(_)));
+		// The credit and the transfer are discarded.
+		assert_eq!(Balances::free_balance(&source_account), 100);
+		assert_eq!(Balances::free_balance(&target_account), 0);
+	});
+}
+
+#[test]
+fn estimate_credit_covers_existential_deposit_and_fee() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(10);
+		let source = H160::from_low_u64_be(0x3000);
+		let source_account = <Test as Config>::AddressMapping::into_account_id(source);
+		let target = H160::from_low_u64_be(0x1000);
+		let target_account = <Test as Config>::AddressMapping::into_account_id(target);
+		let mut config = <Test as Config>::config().clone();
+		config.estimate = true;
+
+		let exit_reason = EVM::with_state_overrides(Vec::new(), || {
+			EVM::credit_estimate_balance(source, U256::from(20), 100_000, Some(U256::one()));
+			// The source can afford the value and the fee, and keep the existential deposit.
+			assert_eq!(Balances::free_balance(&source_account), 20 + 100_000 + 10);
+
+			<Test as Config>::Runner::call(
+				source,
+				target,
+				Vec::new(),
+				U256::from(20),
+				100_000,
+				Some(U256::one()),
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				None,
+				None,
+				&config,
+			)
+			.expect("call executes")
+			.exit_reason
+		});
+		assert!(matches!(exit_reason, ExitReason::Succeed(_)));
+		assert_eq!(Balances::free_balance(&source_account), 0);
+		assert_eq!(Balances::free_balance(&target_account), 0);
+		ExistentialDeposit::set(0);
+	});
+}
+
+#[test]
+fn block_gas_used_is_accumulated() {
+	new_test_ext().execute_with(|| {
+		let call = || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				H160::from_low_u64_be(0x1000),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+			.used_gas
+			.effective
+		};
+
+		let used = call().saturating_add(call());
+		assert_eq!(BlockGasUsed::<Test>::get(), used);
+		assert_eq!(
+			<EVM as BlockUtilization>::utilization(),
+			Permill::from_rational(
+				used.as_u64(),
+				<Test as Config>::BlockGasLimit::get().as_u64()
+			)
+		);
+
+		EVM::on_initialize(System::block_number() + 1);
+		assert_eq!(BlockGasUsed::<Test>::get(), U256::zero());
+		assert_eq!(<EVM as BlockUtilization>::utilization(), Permill::zero());
+	});
+}
+
+#[test]
+fn storage_deposit_is_charged_and_refunded() {
+	new_test_ext().execute_with(|| {
+		let origin = H160::from_low_u64_be(0x3000);
+		let origin_account = <Test as Config>::AddressMapping::into_account_id(origin);
+		let _ = <Test as Config>::Currency::deposit_creating(&origin_account, 1_000);
+		// PUSH1 0x2a PUSH1 0x00 SSTORE
+		let store = H160::from_low_u64_be(0x1000);
+		EVM::create_account(store, hex::decode("602a600055").unwrap());
+		// Stores the calldata size in slot 0: CALLDATASIZE PUSH1 0x00 SSTORE
+		let contract = H160::from_low_u64_be(0x2000);
+		EVM::create_account(contract, hex::decode("36600055").unwrap());
+		let call_from = |origin, target, input: Vec<u8>| {
+			<Test as Config>::Runner::call(
+				origin,
+				target,
+				input,
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+			.exit_reason
+		};
+		let call = |target, input| call_from(origin, target, input);
+
+		StorageDepositPerSlot::set(U256::from(100));
+
+		assert!(matches!(call(contract, vec![1]), ExitReason::Succeed(_)));
+		assert_eq!(Balances::free_balance(&origin_account), 900);
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 100);
+		assert_eq!(
+			StorageDeposits::<Test>::get(contract, H256::zero()),
+			Some((origin_account, 100))
+		);
+
+		// Overwriting an existing slot is free.
+		assert!(matches!(call(contract, vec![1, 2]), ExitReason::Succeed(_)));
+		assert_eq!(Balances::free_balance(&origin_account), 900);
+
+		// Clearing the slot releases the deposit.
+		assert!(matches!(call(contract, vec![]), ExitReason::Succeed(_)));
+		assert_eq!(Balances::free_balance(&origin_account), 1_000);
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 0);
+		assert_eq!(StorageDeposits::<Test>::get(contract, H256::zero()), None);
+
+		// The deposit is released to the depositor, whoever clears the slot.
+		let other = H160::from_low_u64_be(0x3001);
+		let other_account = <Test as Config>::AddressMapping::into_account_id(other);
+		let _ = <Test as Config>::Currency::deposit_creating(&other_account, 1_000);
+		assert!(matches!(call(contract, vec![1]), ExitReason::Succeed(_)));
+		assert!(matches!(
+			call_from(other, contract, vec![]),
+			ExitReason::Succeed(_)
+		));
+		assert_eq!(Balances::free_balance(&origin_account), 1_000);
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 0);
+		assert_eq!(Balances::free_balance(&other_account), 1_000);
+
+		// The origin can't pay the deposit.
+		StorageDepositPerSlot::set(U256::from(2_000));
+		assert_eq!(call(store, vec![]), ExitReason::Error(ExitError::OutOfFund));
+		assert_eq!(
+			<AccountStorages<Test>>::get(store, H256::zero()),
+			H256::zero()
+		);
+		assert_eq!(Balances::free_balance(&origin_account), 1_000);
+	});
+}
+
+#[test]
+fn storage_deposit_is_refunded_after_selfdestruct() {
+	new_test_ext().execute_with(|| {
+		let origin = H160::from_low_u64_be(0x3000);
+		let origin_account = <Test as Config>::AddressMapping::into_account_id(origin);
+		let _ = <Test as Config>::Currency::deposit_creating(&origin_account, 1_000);
+		// Stores 1 in slot 0 when called with calldata, self-destructs otherwise:
+		// CALLDATASIZE PUSH1 0x07 JUMPI PUSH1 0x00 SELFDESTRUCT
+		// JUMPDEST PUSH1 0x01 PUSH1 0x00 SSTORE
+		let contract = H160::from_low_u64_be(0x2000);
+		EVM::create_account(contract, hex::decode("366007576000ff5b6001600055").unwrap());
+		let call = |input: Vec<u8>| {
+			<Test as Config>::Runner::call(
+				origin,
+				contract,
+				input,
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+			.exit_reason
+		};
+
+		StorageDepositPerSlot::set(U256::from(100));
+		assert!(matches!(call(vec![1]), ExitReason::Succeed(_)));
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 100);
+
+		assert!(matches!(call(vec![]), ExitReason::Succeed(_)));
+		assert!(<Suicided<Test>>::contains_key(contract));
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 100);
+
+		// Clearing the storage of the suicided contract releases the deposit.
+		SuicideIdleClearLimit::set(10);
+		EVM::on_idle(1, Weight::MAX);
+		assert!(!<Suicided<Test>>::contains_key(contract));
+		assert_eq!(EVM::iter_account_storages(&contract).count(), 0);
+		assert_eq!(StorageDeposits::<Test>::iter_prefix(contract).count(), 0);
+		assert_eq!(Balances::total_balance_on_hold(&origin_account), 0);
+		assert_eq!(Balances::free_balance(&origin_account), 1_000);
+		SuicideIdleClearLimit::set(0);
+	});
+}
+
+#[test]
+fn metadata_code_gets_cached() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(0xaa);
+
+		crate::Pallet::<Test>::create_account(address, b"Exemple".to_vec());
+
+		let metadata = crate::Pallet::<Test>::account_code_metadata(address);
+		assert_eq!(metadata.size, 7);
+		assert_eq!(
+			metadata.hash,
+			hex_literal::hex!("e8396a990fe08f2402e64a00647e41dadf360ba078a59ba79f55e876e67ed4bc")
+				.into()
+		);
+
+		let metadata2 = <AccountCodesMetadata<Test>>::get(address).expect("to have metadata set");
+		assert_eq!(metadata, metadata2);
+	});
+}
+
+#[test]
+fn metadata_gets_backfilled_for_existing_code() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(0xaa);
+
+		// Code stored without metadata, e.g. deployed before metadata was tracked.
+		<AccountCodes<Test>>::insert(address, b"Exemple".to_vec());
+		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
+
+		let metadata = crate::Pallet::<Test>::account_code_metadata(address);
+		assert_eq!(metadata.size, 7);
+		assert_eq!(<AccountCodesMetadata<Test>>::get(address), Some(metadata));
+		assert_eq!(
+			<CodesByHash<Test>>::get(metadata.hash),
+			Some(b"Exemple".to_vec())
+		);
+	});
+}
+
+#[test]
+fn metadata_empty_dont_code_gets_cached() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(0xaa);
+
+		let metadata = crate::Pallet::<Test>::account_code_metadata(address);
+		assert_eq!(metadata.size, 0);
+		assert_eq!(
+			metadata.hash,
+			hex_literal::hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+				.into()
+		);
+
+		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
+	});
+}
+
+#[cfg(feature = "evm-tracing")]
+#[test]
+fn tracing_collects_steps_and_storage_writes() {
+	new_test_ext().execute_with(|| {
+		// PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+		let callee = H160::from_low_u64_be(0x1000);
+		EVM::create_account(callee, hex::decode("602a60005500").unwrap());
+		// Calls `callee`: PUSH1 0x00 DUP1 DUP1 DUP1 DUP1 PUSH2 0x1000 GAS CALL STOP
+		let caller = H160::from_low_u64_be(0x2000);
+		EVM::create_account(caller, hex::decode("6000808080806110005af100").unwrap());
+
+		let (info, steps) = crate::tracing::trace(&fp_evm::TraceConfig::default(), || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				caller,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+		});
+		assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+
+		let trace: Vec<_> = steps
+			.iter()
+			.map(|step| (step.depth, step.address, step.pc, step.opcode))
+			.collect();
+		assert_eq!(
+			trace,
+			vec![
+				(1, caller, 0, 0x60),
+				(1, caller, 2, 0x80),
+				(1, caller, 3, 0x80),
+				(1, caller, 4, 0x80),
+				(1, caller, 5, 0x80),
+				(1, caller, 6, 0x61),
+				(1, caller, 9, 0x5a),
+				(1, caller, 10, 0xf1),
+				(2, callee, 0, 0x60),
+				(2, callee, 2, 0x60),
+				(2, callee, 4, 0x55),
+				(2, callee, 5, 0x00),
+				(1, caller, 11, 0x00),
+			]
+		);
+		assert_eq!(steps[0].gas_cost, 3);
+		assert_eq!(
+			steps[10].stack,
+			vec![H256::from_low_u64_be(0x2a), H256::zero()]
+		);
+		assert_eq!(
+			steps[10].storage,
+			vec![(H256::zero(), H256::from_low_u64_be(0x2a))]
+		);
+		assert!(steps
+			.iter()
+			.enumerate()
+			.all(|(i, step)| i == 10 || step.storage.is_empty()));
+	});
+}
+
+#[cfg(feature = "evm-tracing")]
+#[test]
+fn tracing_respects_config() {
+	new_test_ext().execute_with(|| {
+		// PUSH1 0x2a PUSH1 0x00 MSTORE STOP
+		let contract = H160::from_low_u64_be(0x1000);
+		EVM::create_account(contract, hex::decode("602a60005200").unwrap());
+		let trace = |config| {
+			crate::tracing::trace(&config, || {
+				<Test as Config>::Runner::call(
+					H160::default(),
+					contract,
+					Vec::new(),
+					U256::zero(),
+					1000000,
+					None,
+					None,
+					None,
+					Vec::new(),
+					false, // non-transactional
+					false, // not sure be validated
+					None,
+					None,
+					<Test as Config>::config(),
+				)
+				.expect("call executes")
+			})
+			.1
+		};
+
+		let steps = trace(fp_evm::TraceConfig::default());
+		assert_eq!(steps.len(), 4);
+		assert_eq!(steps[3].stack, Vec::<H256>::new());
+		assert_eq!(steps[3].memory.len(), 32);
+
+		let steps = trace(fp_evm::TraceConfig {
+			disable_stack: true,
+			disable_memory: true,
+			limit: Some(2),
+		});
+		assert_eq!(
+			steps.iter().map(|step| step.opcode).collect::<Vec<_>>(),
+			vec![0x60, 0x60]
+		);
+		assert!(steps
+			.iter()
+			.all(|step| step.stack.is_empty() && step.memory.is_empty()));
+		// The cost of the dropped `MSTORE` is not added to the last captured step.
+		assert_eq!(steps[1].gas_cost, 3);
+	});
+}
+
+#[test]
+fn state_overrides_are_applied_and_discarded() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x00 SLOAD PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+		let code = hex::decode("60005460005260206000f3").unwrap();
+		let slot = H256::from_low_u64_be(0x2a);
+		let state_override = StateOverride {
+			balance: Some(U256::from(42)),
+			nonce: Some(U256::from(7)),
+			code: Some(code),
+			state: Some(vec![(H256::zero(), slot)]),
+			state_diff: None,
+		};
+		let (account, _) = EVM::account_basic(&address);
+
+		let info = EVM::with_state_overrides(vec![(address, state_override)], || {
+			let (overridden, _) = EVM::account_basic(&address);
+			assert_eq!(overridden.nonce, U256::from(7));
+			assert_eq!(
+				Balances::free_balance(&<Test as Config>::AddressMapping::into_account_id(address)),
+				42
+			);
+
+			<Test as Config>::Runner::call(
+				H160::default(),
+				address,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not sure be validated
+				None,
+				None,
+				<Test as Config>::config(),
+			)
+			.expect("call executes")
+		});
+		assert_eq!(info.value, slot.as_bytes().to_vec());
+
+		assert_eq!(EVM::account_basic(&address).0, account);
+		assert!(<AccountCodes<Test>>::get(address).is_empty());
+		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
+		assert_eq!(
+			<AccountStorages<Test>>::get(address, H256::zero()),
+			H256::zero()
+		);
+	});
+}
+
+#[test]
+fn executed_failed_event_includes_bounded_revert_output() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let call = |target| {
+			assert_ok!(EVM::call(
+				RuntimeOrigin::root(),
+				H160::default(),
+				target,
+				Vec::new(),
+				U256::default(),
+				1000000,
+				U256::from(1_000_000_000),
+				None,
+				None,
+				Vec::new(),
+			));
+		};
+
+		// PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 REVERT
+		let reverts = H160::from_low_u64_be(0x1000);
+		EVM::create_account(reverts, hex::decode("602a60005260206000fd").unwrap());
+		call(reverts);
+		System::assert_last_event(RuntimeEvent::EVM(Event::ExecutedFailed {
+			address: reverts,
+			output: H256::from_low_u64_be(0x2a).as_bytes().to_vec(),
+		}));
+
+		// PUSH2 0x0400 PUSH1 0x00 REVERT
+		let reverts_long = H160::from_low_u64_be(0x2000);
+		EVM::create_account(reverts_long, hex::decode("6104006000fd").unwrap());
+		call(reverts_long);
+		System::assert_last_event(RuntimeEvent::EVM(Event::ExecutedFailed {
+			address: reverts_long,
+			output: vec![0; <Test as Config>::MaxRevertOutputLength::get() as usize],
+		}));
+
+		// INVALID
+		let fails = H160::from_low_u64_be(0x3000);
+		EVM::create_account(fails, hex::decode("fe").unwrap());
+		call(fails);
+		System::assert_last_event(RuntimeEvent::EVM(Event::ExecutedFailed {
+			address: fails,
+			output: Vec::new(),
+		}));
+	});
+}
+
+#[test]
+fn codes_by_hash_are_shared_and_released() {
+	new_test_ext().execute_with(|| {
+		let code = hex::decode("60006000f3").unwrap();
+		let first = H160::from_low_u64_be(0x1000);
+		let second = H160::from_low_u64_be(0x2000);
+		EVM::create_account(first, code.clone());
+		EVM::create_account(second, code.clone());
+
+		let hash = EVM::account_code_metadata(first).hash;
+		assert_eq!(<CodesByHash<Test>>::get(hash), Some(code.clone()));
+		assert_eq!(<CodeReferences<Test>>::get(hash), 2);
+
+		EVM::remove_account(&first);
+		assert_eq!(<CodesByHash<Test>>::get(hash), Some(code));
+		assert_eq!(<CodeReferences<Test>>::get(hash), 1);
+
+		EVM::remove_account(&second);
+		assert!(<CodesByHash<Test>>::get(hash).is_none());
+		assert!(!<CodeReferences<Test>>::contains_key(hash));
+	});
+}
+
+#[test]
+fn codes_by_hash_migration_stores_existing_codes() {
+	use crate::migrations::CodesByHashMigration;
+	use frame_support::{
+		migrations::{SteppedMigration, SteppedMigrationError},
+		traits::{GetStorageVersion, StorageVersion},
+		weights::WeightMeter,
+	};
+
+	new_test_ext().execute_with(|| {
+		let shared = hex::decode("60006000f3").unwrap();
+		let other = hex::decode("60016000f3").unwrap();
+		let first = H160::from_low_u64_be(0x1000);
+		let second = H160::from_low_u64_be(0x2000);
+		let third = H160::from_low_u64_be(0x3000);
+		// Contracts deployed before `CodesByHash`, one with its metadata already computed.
+		<AccountCodes<Test>>::insert(first, &shared);
+		<AccountCodes<Test>>::insert(second, &shared);
+		<AccountCodes<Test>>::insert(third, &other);
+		<AccountCodesMetadata<Test>>::insert(first, CodeMetadata::from_code(&shared));
+		StorageVersion::new(0).put::<EVM>();
+
+		let step_weight = CodesByHashMigration::<Test>::step_weight();
+		assert_eq!(
+			CodesByHashMigration::<Test>::step(
+				None,
+				&mut WeightMeter::with_limit(step_weight.saturating_sub(Weight::from_parts(0, 1)))
+			),
+			Err(SteppedMigrationError::InsufficientWeight {
+				required: step_weight
+			})
+		);
+
+		// A single contract is migrated per step weight.
+		let mut meter = WeightMeter::with_limit(step_weight);
+		let mut cursor = CodesByHashMigration::<Test>::step(None, &mut meter).unwrap();
+		assert!(cursor.is_some());
+		assert_eq!(
+			<CodeReferences<Test>>::iter().map(|(_, r)| r).sum::<u32>(),
+			1
+		);
+
+		while cursor.is_some() {
+			cursor = CodesByHashMigration::<Test>::step(cursor, &mut WeightMeter::new()).unwrap();
+		}
+
+		let shared_hash = EVM::account_code_metadata(second).hash;
+		let other_hash = EVM::account_code_metadata(third).hash;
+		assert_eq!(
+			EVM::account_code_metadata(first),
+			CodeMetadata::from_code(&shared)
+		);
+		assert_eq!(<CodesByHash<Test>>::get(shared_hash), Some(shared));
+		assert_eq!(<CodeReferences<Test>>::get(shared_hash), 2);
+		assert_eq!(<CodesByHash<Test>>::get(other_hash), Some(other));
+		assert_eq!(<CodeReferences<Test>>::get(other_hash), 1);
+		assert_eq!(EVM::on_chain_storage_version(), 1);
+
+		// Migrated chains aren't migrated again.
+		assert_eq!(
+			CodesByHashMigration::<Test>::step(None, &mut WeightMeter::new()),
+			Ok(None)
+		);
+		assert_eq!(<CodeReferences<Test>>::get(shared_hash), 2);
+	});
+}
+
+#[test]
+fn account_code_is_paged() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_low_u64_be(0x1000);
+		EVM::create_account(address, vec![1, 2, 3, 4, 5]);
+
+		assert_eq!(EVM::account_code_at_paged(address, 0, 2), vec![1, 2]);
+		assert_eq!(EVM::account_code_at_paged(address, 3, 10), vec![4, 5]);
+		assert!(EVM::account_code_at_paged(address, 10, 2).is_empty());
+		assert!(EVM::account_code_at_paged(H160::zero(), 0, 2).is_empty());
 	});
 }
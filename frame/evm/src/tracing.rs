@@ -0,0 +1,161 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opcode level tracing of EVM executions.
+//!
+//! The EVM emits tracing events through environmental listeners. This module
+//! installs listeners collecting those events into [`TraceStep`]s, which is
+//! the foundation for `debug_trace*` style RPCs.
+
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use fp_evm::{TraceConfig, TraceStep};
+
+/// Collects the steps of all EVM executions it is listening to.
+struct StepTracer {
+	config: TraceConfig,
+	depth: u32,
+	steps: Vec<TraceStep>,
+	/// Whether the current step is captured, i.e. the limit of steps is not reached.
+	capturing: bool,
+}
+
+impl StepTracer {
+	fn evm_event(&mut self, event: evm::tracing::Event) {
+		use evm::tracing::Event;
+
+		match event {
+			Event::Call { .. } | Event::Create { .. } => self.depth += 1,
+			Event::Exit { .. } => self.depth = self.depth.saturating_sub(1),
+			_ => (),
+		}
+	}
+
+	fn runtime_event(&mut self, event: evm_runtime::tracing::Event) {
+		use evm_runtime::tracing::Event;
+
+		match event {
+			Event::Step {
+				context,
+				opcode,
+				position,
+				stack,
+				memory,
+			} => {
+				self.capturing = self
+					.config
+					.limit
+					.map_or(true, |limit| self.steps.len() < limit as usize);
+				if !self.capturing {
+					return;
+				}
+				self.steps.push(TraceStep {
+					depth: self.depth,
+					address: context.address,
+					pc: position.as_ref().map_or(0, |pc| *pc as u64),
+					opcode: opcode.as_u8(),
+					stack: if self.config.disable_stack {
+						Vec::new()
+					} else {
+						stack.data().clone()
+					},
+					memory: if self.config.disable_memory {
+						Vec::new()
+					} else {
+						memory.data().clone()
+					},
+					..Default::default()
+				});
+			}
+			Event::SStore { index, value, .. } if self.capturing => {
+				if let Some(step) = self.steps.last_mut() {
+					step.storage.push((index, value));
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn gasometer_event(&mut self, event: evm_gasometer::tracing::Event) {
+		use evm_gasometer::tracing::Event;
+
+		let (cost, snapshot) = match event {
+			Event::RecordCost { cost, snapshot } => (cost, snapshot),
+			Event::RecordDynamicCost {
+				gas_cost, snapshot, ..
+			} => (gas_cost, snapshot),
+			_ => return,
+		};
+		if !self.capturing {
+			return;
+		}
+		if let (Some(step), Some(snapshot)) = (self.steps.last_mut(), snapshot) {
+			// Static and dynamic costs of an opcode are recorded separately.
+			if step.gas_cost == 0 {
+				step.gas = snapshot.gas_limit.saturating_sub(snapshot.used_gas);
+			}
+			step.gas_cost = step.gas_cost.saturating_add(cost);
+		}
+	}
+}
+
+struct Listener(Rc<RefCell<StepTracer>>);
+
+impl evm::tracing::EventListener for Listener {
+	fn event(&mut self, event: evm::tracing::Event) {
+		self.0.borrow_mut().evm_event(event);
+	}
+}
+
+impl evm_runtime::tracing::EventListener for Listener {
+	fn event(&mut self, event: evm_runtime::tracing::Event) {
+		self.0.borrow_mut().runtime_event(event);
+	}
+}
+
+impl evm_gasometer::tracing::EventListener for Listener {
+	fn event(&mut self, event: evm_gasometer::tracing::Event) {
+		self.0.borrow_mut().gasometer_event(event);
+	}
+}
+
+/// Run `f`, returning its result along with the steps of every EVM execution
+/// it performs, e.g. a call to the [`Runner`](crate::Runner) or the
+/// application of an Ethereum transaction.
+///
+/// The captured steps and their contents are restricted by `config`.
+pub fn trace<R>(config: &TraceConfig, f: impl FnOnce() -> R) -> (R, Vec<TraceStep>) {
+	let tracer = Rc::new(RefCell::new(StepTracer {
+		config: *config,
+		depth: 0,
+		steps: Vec::new(),
+		capturing: true,
+	}));
+	let mut evm_listener = Listener(tracer.clone());
+	let mut runtime_listener = Listener(tracer.clone());
+	let mut gasometer_listener = Listener(tracer.clone());
+
+	let result = evm::tracing::using(&mut evm_listener, || {
+		evm_runtime::tracing::using(&mut runtime_listener, || {
+			evm_gasometer::tracing::using(&mut gasometer_listener, f)
+		})
+	});
+
+	let steps = core::mem::take(&mut tracer.borrow_mut().steps);
+	(result, steps)
+}
@@ -49,6 +49,14 @@ use core::marker::PhantomData;
 /// Weight functions needed for pallet_evm.
 pub trait WeightInfo {
 	fn withdraw() -> Weight;
+	fn set_hardfork() -> Weight;
+	fn schedule_hardfork() -> Weight;
+	fn force_create(c: u32, s: u32, ) -> Weight;
+	fn set_create_allowed() -> Weight;
+	fn set_call_allowed() -> Weight;
+	fn pause_contract() -> Weight;
+	fn unpause_contract() -> Weight;
+	fn set_sponsor_allowance() -> Weight;
 }
 
 /// Weights for pallet_evm using the Substrate node and recommended hardware.
@@ -61,6 +69,80 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		// Minimum execution time: 1_564_000 picoseconds.
 		Weight::from_parts(1_696_000, 0)
 	}
+	/// Storage: `EVM::ScheduledHardfork` (r:0 w:1)
+	/// Proof: `EVM::ScheduledHardfork` (`max_values`: Some(1), `max_size`: Some(5), added: 500, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::ChainConfig` (r:0 w:1)
+	/// Proof: `EVM::ChainConfig` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	fn set_hardfork() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `EVM::ScheduledHardfork` (r:0 w:1)
+	/// Proof: `EVM::ScheduledHardfork` (`max_values`: Some(1), `max_size`: Some(5), added: 500, mode: `MaxEncodedLen`)
+	fn schedule_hardfork() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::AccountCodes` (r:1 w:1)
+	/// Proof: `EVM::AccountCodes` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `EVM::Suicided` (r:1 w:0)
+	/// Proof: `EVM::Suicided` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::AccountCodesMetadata` (r:1 w:1)
+	/// Proof: `EVM::AccountCodesMetadata` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::CodeReferences` (r:1 w:1)
+	/// Proof: `EVM::CodeReferences` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::CodesByHash` (r:0 w:1)
+	/// Proof: `EVM::CodesByHash` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `EVM::AccountStorages` (r:0 w:100)
+	/// Proof: `EVM::AccountStorages` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 24576]`.
+	/// The range of component `s` is `[0, 100]`.
+	fn force_create(_c: u32, s: u32, ) -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 7665)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+	/// Storage: `EVM::CreateAllowlist` (r:0 w:1)
+	/// Proof: `EVM::CreateAllowlist` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn set_create_allowed() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::CallAllowlist` (r:0 w:1)
+	/// Proof: `EVM::CallAllowlist` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn set_call_allowed() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::PausedContracts` (r:0 w:1)
+	/// Proof: `EVM::PausedContracts` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn pause_contract() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::PausedContracts` (r:0 w:1)
+	/// Proof: `EVM::PausedContracts` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn unpause_contract() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::SponsorAllowances` (r:0 w:1)
+	/// Proof: `EVM::SponsorAllowances` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn set_sponsor_allowance() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -72,4 +154,78 @@ impl WeightInfo for () {
 		// Minimum execution time: 1_564_000 picoseconds.
 		Weight::from_parts(1_696_000, 0)
 	}
+	/// Storage: `EVM::ScheduledHardfork` (r:0 w:1)
+	/// Proof: `EVM::ScheduledHardfork` (`max_values`: Some(1), `max_size`: Some(5), added: 500, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::ChainConfig` (r:0 w:1)
+	/// Proof: `EVM::ChainConfig` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	fn set_hardfork() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `EVM::ScheduledHardfork` (r:0 w:1)
+	/// Proof: `EVM::ScheduledHardfork` (`max_values`: Some(1), `max_size`: Some(5), added: 500, mode: `MaxEncodedLen`)
+	fn schedule_hardfork() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::AccountCodes` (r:1 w:1)
+	/// Proof: `EVM::AccountCodes` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `EVM::Suicided` (r:1 w:0)
+	/// Proof: `EVM::Suicided` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::AccountCodesMetadata` (r:1 w:1)
+	/// Proof: `EVM::AccountCodesMetadata` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::CodeReferences` (r:1 w:1)
+	/// Proof: `EVM::CodeReferences` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `EVM::CodesByHash` (r:0 w:1)
+	/// Proof: `EVM::CodesByHash` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `EVM::AccountStorages` (r:0 w:100)
+	/// Proof: `EVM::AccountStorages` (`max_values`: None, `max_size`: Some(116), added: 2591, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[1, 24576]`.
+	/// The range of component `s` is `[0, 100]`.
+	fn force_create(_c: u32, s: u32, ) -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 7665)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+	/// Storage: `EVM::CreateAllowlist` (r:0 w:1)
+	/// Proof: `EVM::CreateAllowlist` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn set_create_allowed() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::CallAllowlist` (r:0 w:1)
+	/// Proof: `EVM::CallAllowlist` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn set_call_allowed() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::PausedContracts` (r:0 w:1)
+	/// Proof: `EVM::PausedContracts` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn pause_contract() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::PausedContracts` (r:0 w:1)
+	/// Proof: `EVM::PausedContracts` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	fn unpause_contract() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `EVM::SponsorAllowances` (r:0 w:1)
+	/// Proof: `EVM::SponsorAllowances` (`max_values`: None, `max_size`: Some(104), added: 2579, mode: `MaxEncodedLen`)
+	fn set_sponsor_allowance() -> Weight {
+		// Not benchmarked yet, the measured base of `withdraw` and the storage accesses.
+		Weight::from_parts(1_696_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
@@ -49,7 +49,7 @@ construct_runtime!(
 	pub enum Runtime {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
-		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>},
+		Evm: pallet_evm::{Pallet, Call, Storage, Event<T>, HoldReason},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 	}
 );
@@ -227,6 +227,7 @@ parameter_types! {
 		block_gas_limit.saturating_div(MAX_POV_SIZE)
 	};
 	pub SuicideQuickClearLimit: u32 = 0;
+	pub SuicideIdleClearLimit: u32 = 0;
 }
 
 impl pallet_evm::Config for Runtime {
@@ -240,6 +241,7 @@ impl pallet_evm::Config for Runtime {
 	type AddressMapping = AccountId;
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type PrecompilesType = Precompiles<Runtime>;
 	type PrecompilesValue = PrecompilesValue;
 	type ChainId = ();
@@ -247,12 +249,23 @@ impl pallet_evm::Config for Runtime {
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type CreateAddressScheme = ();
 	type FindAuthor = ();
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type SuicideQuickClearLimit = SuicideQuickClearLimit;
+	type SuicideIdleClearLimit = SuicideIdleClearLimit;
 	type GasLimitStorageGrowthRatio = ();
 	type Timestamp = Timestamp;
 	type WeightInfo = pallet_evm::weights::SubstrateWeight<Runtime>;
+	type MaxCodeSize = frame_support::traits::ConstU32<{ 24 * 1024 }>;
+	type MaxInitCodeSize = frame_support::traits::ConstU32<{ 48 * 1024 }>;
+	type MaxRevertOutputLength = frame_support::traits::ConstU32<256>;
+	type CreateOriginFilter = Everything;
+	type CallOriginFilter = Everything;
+	type StorageDepositPerSlot = ();
+	type GasQuota = ();
+	type GasQuotaPeriod = ();
+	type PauseOrigin = frame_system::EnsureRoot<Self::AccountId>;
 }
 
 parameter_types! {
@@ -59,4 +59,10 @@ pub trait AccountProvider {
 	///
 	/// Incremented with each new transaction submitted by the account.
 	fn inc_account_nonce(who: &Self::AccountId);
+
+	/// Set a particular account's nonce value.
+	///
+	/// Used to apply state overrides when executing calls off-chain. Does nothing by default,
+	/// in which case nonce overrides are ignored.
+	fn set_account_nonce(_who: &Self::AccountId, _nonce: Self::Nonce) {}
 }
@@ -23,6 +23,7 @@ extern crate alloc;
 mod account_provider;
 mod precompile;
 mod storage_oog;
+mod tracing;
 mod validation;
 
 use alloc::{collections::BTreeMap, vec::Vec};
@@ -32,7 +33,7 @@ use scale_info::TypeInfo;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use sp_core::{H160, H256, U256};
-use sp_runtime::Perbill;
+use sp_runtime::{Perbill, Permill};
 
 pub use evm::{
 	backend::{Basic as Account, Log},
@@ -47,6 +48,7 @@ pub use self::{
 		PrecompileSet, Transfer,
 	},
 	storage_oog::{handle_storage_oog, set_storage_oog},
+	tracing::{TraceConfig, TraceStep},
 	validation::{
 		CheckEvmTransaction, CheckEvmTransactionConfig, CheckEvmTransactionInput,
 		TransactionValidationError,
@@ -63,6 +65,23 @@ pub struct Vicinity {
 	pub origin: H160,
 }
 
+/// Overrides applied to an account before executing a call, honoring `eth_call`
+/// state overrides.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StateOverride {
+	/// Balance to set for the account.
+	pub balance: Option<U256>,
+	/// Nonce to set for the account.
+	pub nonce: Option<U256>,
+	/// Code to set for the account.
+	pub code: Option<Vec<u8>>,
+	/// Storage replacing the whole account storage.
+	pub state: Option<Vec<(H256, H256)>>,
+	/// Storage slots to set, applied after `state`.
+	pub state_diff: Option<Vec<(H256, H256)>>,
+}
+
 /// `System::Account` 16(hash) + 20 (key) + 72 (AccountInfo::max_encoded_len)
 pub const ACCOUNT_BASIC_PROOF_SIZE: u64 = 108;
 /// `AccountCodesMetadata` read, temtatively 16 (hash) + 20 (key) + 40 (CodeMetadata).
@@ -75,6 +94,15 @@ pub const WRITE_PROOF_SIZE: u64 = 32;
 pub const IS_EMPTY_CHECK_PROOF_SIZE: u64 = 93;
 /// `AccountCodes` key size. 16 (hash) + 20 (key)
 pub const ACCOUNT_CODES_KEY_SIZE: u64 = 36;
+/// `PausedContracts` read. 16 (hash) + 20 (key)
+pub const PAUSED_CONTRACTS_PROOF_SIZE: u64 = 36;
+/// `StorageDeposits` entry. 16 (hash1) + 20 (key1) + 16 (hash2) + 32 (key2) + 32 (depositor) +
+/// 32 (deposit)
+pub const STORAGE_DEPOSIT_PROOF_SIZE: u64 = 148;
+/// `GasQuotaUsage` entry. 8 (hash1) + 4 (key1) + 16 (hash2) + 20 (key2) + 8 (used gas)
+pub const GAS_QUOTA_USAGE_PROOF_SIZE: u64 = 56;
+/// `GasQuotaPeriods` entry. 8 (hash) + 4 (key)
+pub const GAS_QUOTA_PERIOD_PROOF_SIZE: u64 = 12;
 
 pub enum AccessedStorage {
 	AccountCodes(H160),
@@ -250,6 +278,12 @@ impl FeeCalculator for () {
 	}
 }
 
+/// Utilization of the current block, driving the adjustment of the base fee.
+pub trait BlockUtilization {
+	/// Used ratio of the block capacity.
+	fn utilization() -> Permill;
+}
+
 /// `WeightPerGas` is an approximate ratio of the amount of Weight per Gas.
 /// u64 works for approximations because Weight is a very small unit compared to gas.
 ///
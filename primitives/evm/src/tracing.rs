@@ -0,0 +1,59 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+use scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256};
+
+/// A single opcode step of a traced EVM execution.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TraceStep {
+	/// Call depth of the step, the outermost frame being `1`.
+	pub depth: u32,
+	/// Address of the executing contract.
+	pub address: H160,
+	/// Program counter.
+	pub pc: u64,
+	/// Executed opcode.
+	pub opcode: u8,
+	/// Gas left before the step.
+	pub gas: u64,
+	/// Gas charged by the step.
+	pub gas_cost: u64,
+	/// Stack before the step, bottom first.
+	pub stack: Vec<H256>,
+	/// Memory before the step.
+	pub memory: Vec<u8>,
+	/// Storage slots written by the step.
+	pub storage: Vec<(H256, H256)>,
+}
+
+/// Options of a traced EVM execution.
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TraceConfig {
+	/// Do not capture the stack of each step.
+	pub disable_stack: bool,
+	/// Do not capture the memory of each step.
+	pub disable_memory: bool,
+	/// Maximum number of steps to capture, the following steps are dropped.
+	pub limit: Option<u32>,
+}
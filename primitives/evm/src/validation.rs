@@ -43,6 +43,11 @@ pub struct CheckEvmTransactionConfig<'config> {
 	pub base_fee: U256,
 	pub chain_id: u64,
 	pub is_transactional: bool,
+	/// Whether the transaction is executed to estimate its gas usage. The sender then only needs
+	/// to afford the fee, the `call` and `create` runtime APIs credit it the balance it misses.
+	pub is_estimate: bool,
+	/// Maximum distance between a pool transaction nonce and the account nonce.
+	pub max_nonce_gap: Option<U256>,
 }
 
 #[derive(Debug)]
@@ -59,28 +64,40 @@ pub struct CheckEvmTransaction<'config, E: From<TransactionValidationError>> {
 #[derive(num_enum::FromPrimitive, num_enum::IntoPrimitive, Debug)]
 pub enum TransactionValidationError {
 	/// The transaction gas limit is too low
-	GasLimitTooLow,
+	GasLimitTooLow = 0,
 	/// The transaction gas limit is too hign
-	GasLimitTooHigh,
+	GasLimitTooHigh = 1,
 	/// The transaction gas price is too low
-	GasPriceTooLow,
+	GasPriceTooLow = 2,
 	/// The transaction priority fee is too high
-	PriorityFeeTooHigh,
+	PriorityFeeTooHigh = 3,
 	/// The transaction balance is too low
-	BalanceTooLow,
+	BalanceTooLow = 4,
 	/// The transaction nonce is too low
-	TxNonceTooLow,
+	TxNonceTooLow = 5,
 	/// The transaction nonce is too high
-	TxNonceTooHigh,
+	TxNonceTooHigh = 6,
 	/// The transaction fee input is invalid
-	InvalidFeeInput,
+	InvalidFeeInput = 7,
 	/// The chain id is incorrect
-	InvalidChainId,
+	InvalidChainId = 8,
 	/// The transaction signature is invalid
-	InvalidSignature,
+	InvalidSignature = 9,
 	/// Unknown error
 	#[num_enum(default)]
-	UnknownError,
+	UnknownError = 10,
+	// Variants added later take the next free values, keeping the `InvalidTransaction::Custom`
+	// codes of the existing ones stable.
+	/// The transaction sender has code deployed (EIP-3607)
+	SenderHasCode = 11,
+	/// The transaction init code exceeds the maximum size (EIP-3860)
+	MaxInitCodeSizeExceeded = 12,
+	/// The transaction gas limit exceeds the remaining gas quota of the sender
+	GasQuotaExceeded = 13,
+	/// The transaction sender is not allowed to create contracts
+	CreateOriginNotAllowed = 14,
+	/// The transaction sender is not allowed to call
+	CallOriginNotAllowed = 15,
 }
 
 impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config, E> {
@@ -103,6 +120,11 @@ impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config,
 		if self.transaction.nonce < who.nonce {
 			return Err(TransactionValidationError::TxNonceTooLow.into());
 		}
+		if let Some(max_nonce_gap) = self.config.max_nonce_gap {
+			if self.transaction.nonce > who.nonce.saturating_add(max_nonce_gap) {
+				return Err(TransactionValidationError::TxNonceTooHigh.into());
+			}
+		}
 		self.validate_common()
 	}
 
@@ -128,7 +150,8 @@ impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config,
 	pub fn with_base_fee(&self) -> Result<&Self, E> {
 		// Get fee data from either a legacy or typed transaction input.
 		let (gas_price, _) = self.transaction_fee_input()?;
-		if self.config.is_transactional || gas_price > U256::zero() {
+		// Base fee is not enforced when estimating gas.
+		if !self.config.is_estimate && (self.config.is_transactional || gas_price > U256::zero()) {
 			// Transaction max fee is at least the current base fee.
 			if gas_price < self.config.base_fee {
 				return Err(TransactionValidationError::GasPriceTooLow.into());
@@ -151,8 +174,14 @@ impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config,
 		// Fee for Legacy or EIP-2930 transaction is calculated using
 		// the provided `gas_price`.
 		let fee = max_fee_per_gas.saturating_mul(self.transaction.gas_limit);
+		//
+		// When estimating gas, the account only needs to afford the fee.
 		if self.config.is_transactional || fee > U256::zero() {
-			let total_payment = self.transaction.value.saturating_add(fee);
+			let total_payment = if self.config.is_estimate {
+				fee
+			} else {
+				self.transaction.value.saturating_add(fee)
+			};
 			if who.balance < total_payment {
 				return Err(TransactionValidationError::BalanceTooLow.into());
 			}
@@ -160,6 +189,34 @@ impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config,
 		Ok(self)
 	}
 
+	/// Transaction gas limit is within the `remaining` gas quota of the sender, if any.
+	pub fn with_gas_quota(&self, remaining: Option<U256>) -> Result<&Self, E> {
+		if let Some(remaining) = remaining {
+			if self.config.is_transactional
+				&& !self.config.is_estimate
+				&& self.transaction.gas_limit > remaining
+			{
+				return Err(TransactionValidationError::GasQuotaExceeded.into());
+			}
+		}
+		Ok(self)
+	}
+
+	/// Like [`Self::with_balance_for`], with the fee paid by `sponsor` and the value by `who`.
+	pub fn with_sponsored_balance_for(&self, who: &Account, sponsor: &Account) -> Result<&Self, E> {
+		let (max_fee_per_gas, _) = self.transaction_fee_input()?;
+		let fee = max_fee_per_gas.saturating_mul(self.transaction.gas_limit);
+		if self.config.is_transactional || fee > U256::zero() {
+			if sponsor.balance < fee {
+				return Err(TransactionValidationError::BalanceTooLow.into());
+			}
+			if !self.config.is_estimate && who.balance < self.transaction.value {
+				return Err(TransactionValidationError::BalanceTooLow.into());
+			}
+		}
+		Ok(self)
+	}
+
 	// Returns the max_fee_per_gas (or gas_price for legacy txns) as well as an optional
 	// effective_gas_price for EIP-1559 transactions. effective_gas_price represents
 	// the total (fee + tip) that would be paid given the current base_fee.
@@ -234,6 +291,16 @@ impl<'config, E: From<TransactionValidationError>> CheckEvmTransaction<'config,
 				return Err(TransactionValidationError::GasLimitTooLow.into());
 			}
 
+			// EIP-3860: contract creation init code must not exceed the configured limit.
+			if let (None, Some(max_initcode_size)) = (
+				self.transaction.to,
+				self.config.evm_config.max_initcode_size,
+			) {
+				if self.transaction.input.len() > max_initcode_size {
+					return Err(TransactionValidationError::MaxInitCodeSizeExceeded.into());
+				}
+			}
+
 			// Transaction gas limit is within the upper bound block gas limit.
 			if self.transaction.gas_limit > self.config.block_gas_limit {
 				return Err(TransactionValidationError::GasLimitTooHigh.into());
@@ -260,6 +327,11 @@ mod tests {
 		InvalidFeeInput,
 		InvalidChainId,
 		InvalidSignature,
+		SenderHasCode,
+		MaxInitCodeSizeExceeded,
+		GasQuotaExceeded,
+		CreateOriginNotAllowed,
+		CallOriginNotAllowed,
 		UnknownError,
 	}
 
@@ -278,6 +350,15 @@ mod tests {
 				TransactionValidationError::InvalidFeeInput => TestError::InvalidFeeInput,
 				TransactionValidationError::InvalidChainId => TestError::InvalidChainId,
 				TransactionValidationError::InvalidSignature => TestError::InvalidSignature,
+				TransactionValidationError::SenderHasCode => TestError::SenderHasCode,
+				TransactionValidationError::MaxInitCodeSizeExceeded => {
+					TestError::MaxInitCodeSizeExceeded
+				}
+				TransactionValidationError::GasQuotaExceeded => TestError::GasQuotaExceeded,
+				TransactionValidationError::CreateOriginNotAllowed => {
+					TestError::CreateOriginNotAllowed
+				}
+				TransactionValidationError::CallOriginNotAllowed => TestError::CallOriginNotAllowed,
 				TransactionValidationError::UnknownError => TestError::UnknownError,
 			}
 		}
@@ -288,13 +369,18 @@ mod tests {
 		pub blockchain_base_fee: U256,
 		pub blockchain_chain_id: u64,
 		pub is_transactional: bool,
+		pub is_estimate: bool,
+		pub max_nonce_gap: Option<U256>,
 		pub chain_id: Option<u64>,
+		pub to: Option<H160>,
+		pub input: Vec<u8>,
 		pub nonce: U256,
 		pub gas_limit: U256,
 		pub gas_price: Option<U256>,
 		pub max_fee_per_gas: Option<U256>,
 		pub max_priority_fee_per_gas: Option<U256>,
 		pub value: U256,
+		pub access_list: Vec<(H160, Vec<H256>)>,
 		pub weight_limit: Option<Weight>,
 		pub proof_size_base_cost: Option<u64>,
 	}
@@ -306,13 +392,18 @@ mod tests {
 				blockchain_base_fee: U256::from(1_000_000_000u128),
 				blockchain_chain_id: 42u64,
 				is_transactional: true,
+				is_estimate: false,
+				max_nonce_gap: None,
 				chain_id: Some(42u64),
+				to: Some(H160::default()),
+				input: vec![],
 				nonce: U256::zero(),
 				gas_limit: U256::from(21_000u64),
 				gas_price: None,
 				max_fee_per_gas: Some(U256::from(1_000_000_000u128)),
 				max_priority_fee_per_gas: Some(U256::from(1_000_000_000u128)),
 				value: U256::from(1u8),
+				access_list: vec![],
 				weight_limit: None,
 				proof_size_base_cost: None,
 			}
@@ -325,13 +416,18 @@ mod tests {
 			blockchain_base_fee,
 			blockchain_chain_id,
 			is_transactional,
+			is_estimate,
+			max_nonce_gap,
 			chain_id,
+			to,
+			input,
 			nonce,
 			gas_limit,
 			gas_price,
 			max_fee_per_gas,
 			max_priority_fee_per_gas,
 			value,
+			access_list,
 			weight_limit,
 			proof_size_base_cost,
 		} = input;
@@ -342,18 +438,20 @@ mod tests {
 				base_fee: blockchain_base_fee,
 				chain_id: blockchain_chain_id,
 				is_transactional,
+				is_estimate,
+				max_nonce_gap,
 			},
 			CheckEvmTransactionInput {
 				chain_id,
-				to: Some(H160::default()),
-				input: vec![],
+				to,
+				input,
 				nonce,
 				gas_limit,
 				gas_price,
 				max_fee_per_gas,
 				max_priority_fee_per_gas,
 				value,
-				access_list: vec![],
+				access_list,
 			},
 			weight_limit,
 			proof_size_base_cost,
@@ -380,6 +478,27 @@ mod tests {
 		})
 	}
 
+	fn transaction_with_access_list<'config>(
+		gas_limit: U256,
+	) -> CheckEvmTransaction<'config, TestError> {
+		test_env(TestCase {
+			gas_limit,
+			access_list: vec![(H160::default(), vec![H256::default()])],
+			..Default::default()
+		})
+	}
+
+	fn transaction_create_with_init_code_size<'config>(
+		size: usize,
+	) -> CheckEvmTransaction<'config, TestError> {
+		test_env(TestCase {
+			to: None,
+			input: vec![0u8; size],
+			gas_limit: U256::from(1_000_000u64),
+			..Default::default()
+		})
+	}
+
 	fn transaction_gas_limit_low_proof_size<'config>(
 		is_transactional: bool,
 	) -> CheckEvmTransaction<'config, TestError> {
@@ -520,6 +639,27 @@ mod tests {
 		assert!(res.is_ok());
 	}
 
+	// Nonce beyond the maximum nonce gap fails in pool.
+	#[test]
+	fn validate_in_pool_fails_nonce_gap_too_high() {
+		let who = Account {
+			balance: U256::from(1_000_000u128),
+			nonce: U256::from(1u8),
+		};
+		let transaction_with_max_nonce_gap = |max_nonce_gap: u8| {
+			test_env(TestCase {
+				nonce: U256::from(10u8),
+				max_nonce_gap: Some(U256::from(max_nonce_gap)),
+				..Default::default()
+			})
+		};
+		let res = transaction_with_max_nonce_gap(9).validate_in_pool_for(&who);
+		assert!(res.is_ok());
+		let res = transaction_with_max_nonce_gap(8).validate_in_pool_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::TxNonceTooHigh);
+	}
+
 	// Nonce too high fails in block.
 	#[test]
 	fn validate_in_block_fails_nonce_too_high() {
@@ -568,6 +708,71 @@ mod tests {
 		assert!(res.is_ok());
 	}
 
+	// Gas limit not covering the access list intrinsic cost fails in pool and in block.
+	#[test]
+	fn validate_in_pool_and_block_fails_gas_limit_too_low_for_access_list() {
+		let who = Account {
+			balance: U256::from(1_000_000u128),
+			nonce: U256::zero(),
+		};
+		// 21_000 base cost + 2_400 per address + 1_900 per storage key.
+		let test = transaction_with_access_list(U256::from(25_299u64));
+		// Pool
+		let res = test.validate_in_pool_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::GasLimitTooLow);
+		// Block
+		let res = test.validate_in_block_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::GasLimitTooLow);
+	}
+
+	// Gas limit covering the access list intrinsic cost succeeds in pool and in block.
+	#[test]
+	fn validate_in_pool_and_block_succeeds_gas_limit_for_access_list() {
+		let who = Account {
+			balance: U256::from(1_000_000u128),
+			nonce: U256::zero(),
+		};
+		let test = transaction_with_access_list(U256::from(25_300u64));
+		// Pool
+		assert!(test.validate_in_pool_for(&who).is_ok());
+		// Block
+		assert!(test.validate_in_block_for(&who).is_ok());
+	}
+
+	// Init code within the EIP-3860 limit succeeds in pool and in block.
+	#[test]
+	fn validate_in_pool_and_block_succeeds_max_init_code_size() {
+		let who = Account {
+			balance: U256::from(1_000_000u128),
+			nonce: U256::zero(),
+		};
+		let test = transaction_create_with_init_code_size(49_152);
+		// Pool
+		assert!(test.validate_in_pool_for(&who).is_ok());
+		// Block
+		assert!(test.validate_in_block_for(&who).is_ok());
+	}
+
+	// Init code above the EIP-3860 limit fails in pool and in block.
+	#[test]
+	fn validate_in_pool_and_block_fails_max_init_code_size_exceeded() {
+		let who = Account {
+			balance: U256::from(1_000_000u128),
+			nonce: U256::zero(),
+		};
+		let test = transaction_create_with_init_code_size(49_153);
+		// Pool
+		let res = test.validate_in_pool_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::MaxInitCodeSizeExceeded);
+		// Block
+		let res = test.validate_in_block_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::MaxInitCodeSizeExceeded);
+	}
+
 	// Gas limit too low for proof size recording transactional fails in pool and in block.
 	#[test]
 	fn validate_in_pool_and_block_transactional_fails_gas_limit_too_low_proof_size() {
@@ -735,6 +940,109 @@ mod tests {
 		assert_eq!(res.unwrap_err(), TestError::BalanceTooLow);
 	}
 
+	// Sponsored transactions split the fee and the value between the sponsor and the sender.
+	#[test]
+	fn validate_sponsored_balance() {
+		let sponsor = Account {
+			balance: U256::from(21_000_000_000_000u128),
+			nonce: U256::zero(),
+		};
+		let who = Account {
+			balance: U256::one(),
+			nonce: U256::zero(),
+		};
+		let test = default_transaction(true);
+		let res = test.with_sponsored_balance_for(&who, &sponsor);
+		assert!(res.is_ok());
+		// The sender does not cover the value.
+		let res = test.with_sponsored_balance_for(&Account::default(), &sponsor);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::BalanceTooLow);
+		// The sponsor does not cover the fee.
+		let sponsor = Account {
+			balance: U256::from(20_999_999_999_999u128),
+			nonce: U256::zero(),
+		};
+		let who = Account {
+			balance: U256::max_value(),
+			nonce: U256::zero(),
+		};
+		let res = test.with_sponsored_balance_for(&who, &sponsor);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::BalanceTooLow);
+	}
+
+	// Gas limit must fit the remaining gas quota of transactional senders.
+	#[test]
+	fn validate_gas_quota() {
+		let test = default_transaction(true);
+		assert!(test.with_gas_quota(None).is_ok());
+		assert!(test.with_gas_quota(Some(U256::from(21_000u64))).is_ok());
+		let res = test.with_gas_quota(Some(U256::from(20_999u64)));
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::GasQuotaExceeded);
+		// Non-transactional
+		let test = default_transaction(false);
+		assert!(test.with_gas_quota(Some(U256::zero())).is_ok());
+	}
+
+	// `InvalidTransaction::Custom` codes of the validation errors are stable.
+	#[test]
+	fn validation_error_codes_are_stable() {
+		assert_eq!(TransactionValidationError::GasLimitTooLow as u8, 0);
+		assert_eq!(TransactionValidationError::InvalidSignature as u8, 9);
+		assert_eq!(TransactionValidationError::UnknownError as u8, 10);
+		assert_eq!(TransactionValidationError::SenderHasCode as u8, 11);
+		assert_eq!(
+			TransactionValidationError::MaxInitCodeSizeExceeded as u8,
+			12
+		);
+		assert_eq!(TransactionValidationError::GasQuotaExceeded as u8, 13);
+		assert_eq!(TransactionValidationError::CreateOriginNotAllowed as u8, 14);
+		assert_eq!(TransactionValidationError::CallOriginNotAllowed as u8, 15);
+		assert!(matches!(
+			TransactionValidationError::from(u8::MAX),
+			TransactionValidationError::UnknownError
+		));
+	}
+
+	// Base fee is not enforced when estimating gas.
+	#[test]
+	fn validate_base_fee_with_max_fee_too_low_estimate_succeeds() {
+		let test = test_env(TestCase {
+			max_fee_per_gas: Some(U256::from(1u8)),
+			max_priority_fee_per_gas: None,
+			is_transactional: false,
+			is_estimate: true,
+			..Default::default()
+		});
+		let res = test.with_base_fee();
+		assert!(res.is_ok());
+	}
+
+	// Only the fee must be affordable when estimating gas.
+	#[test]
+	fn validate_balance_estimate_excludes_value() {
+		let who = Account {
+			balance: U256::from(21_000_000_000_000u128),
+			nonce: U256::zero(),
+		};
+		let test = test_env(TestCase {
+			is_transactional: false,
+			is_estimate: true,
+			..Default::default()
+		});
+		let res = test.with_balance_for(&who);
+		assert!(res.is_ok());
+		let who = Account {
+			balance: U256::from(20_999_999_999_999u128),
+			nonce: U256::zero(),
+		};
+		let res = test.with_balance_for(&who);
+		assert!(res.is_err());
+		assert_eq!(res.unwrap_err(), TestError::BalanceTooLow);
+	}
+
 	// Fee not set on transactional fails.
 	#[test]
 	fn validate_non_fee_transactional_fails() {
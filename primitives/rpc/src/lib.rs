@@ -84,7 +84,7 @@ impl<B: BlockT, C> RuntimeStorageOverride<B, C> for () {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
-	#[api_version(5)]
+	#[api_version(7)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -98,6 +98,13 @@ sp_api::decl_runtime_apis! {
 		/// For a given account address, returns pallet_evm::AccountCodes.
 		fn account_code_at(address: Address) -> Vec<u8>;
 
+		/// For a given account address, returns `len` bytes of pallet_evm::AccountCodes
+		/// starting at `offset`.
+		fn account_code_at_paged(address: Address, offset: u32, len: u32) -> Vec<u8>;
+
+		/// Returns the contract code with the given code hash, from pallet_evm::CodesByHash.
+		fn code_by_hash(code_hash: H256) -> Option<Vec<u8>>;
+
 		/// Returns the converted FindAuthor::find_author authority id.
 		fn author() -> Address;
 
@@ -141,6 +148,19 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfo::<Vec<u8>>, sp_runtime::DispatchError>;
+		#[changed_in(6)]
+		fn call(
+			from: Address,
+			to: Address,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
 		fn call(
 			from: Address,
 			to: Address,
@@ -152,6 +172,7 @@ sp_api::decl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			state_overrides: Option<Vec<(Address, fp_evm::StateOverride)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
 
 		/// Returns a frame_ethereum::create response.
@@ -188,6 +209,7 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfo::<Address>, sp_runtime::DispatchError>;
+		#[changed_in(6)]
 		fn create(
 			from: Address,
 			data: Vec<u8>,
@@ -199,6 +221,18 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Address>, sp_runtime::DispatchError>;
+		fn create(
+			from: Address,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			state_overrides: Option<Vec<(Address, fp_evm::StateOverride)>>,
+		) -> Result<fp_evm::ExecutionInfoV2::<Address>, sp_runtime::DispatchError>;
 
 		/// Return the current block. Legacy.
 		#[changed_in(2)]
@@ -270,6 +304,19 @@ sp_api::decl_runtime_apis! {
 		#[changed_in(2)]
 		fn convert_transaction(transaction: ethereum::TransactionV0) -> <Block as BlockT>::Extrinsic;
 	}
+
+	/// API for opcode level tracing of Ethereum transactions, backing `debug_trace*` RPCs.
+	/// Runtimes implement it with `pallet_evm::tracing::trace`, which requires the
+	/// `evm-tracing` feature of `pallet-evm`.
+	pub trait EthereumTracingRuntimeApi {
+		/// Apply `extrinsics` on top of the initialized block, then apply `transaction`
+		/// and return the steps of its EVM execution, restricted by `config`.
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &ethereum::TransactionV2,
+			config: fp_evm::TraceConfig,
+		) -> Result<Vec<fp_evm::TraceStep>, sp_runtime::DispatchError>;
+	}
 }
 
 /// Fallback transaction converter when the `ConvertTransactionRuntimeApi` is not available. For almost all
@@ -348,6 +348,7 @@ parameter_types! {
 	pub PrecompilesValue: FrontierPrecompiles<Runtime> = FrontierPrecompiles::<_>::new();
 	pub WeightPerGas: Weight = Weight::from_parts(weight_per_gas(BLOCK_GAS_LIMIT, NORMAL_DISPATCH_RATIO, WEIGHT_MILLISECS_PER_BLOCK), 0);
 	pub SuicideQuickClearLimit: u32 = 0;
+	pub SuicideIdleClearLimit: u32 = 0;
 }
 
 impl pallet_evm::Config for Runtime {
@@ -361,6 +362,7 @@ impl pallet_evm::Config for Runtime {
 	type AddressMapping = IdentityAddressMapping;
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type PrecompilesType = FrontierPrecompiles<Self>;
 	type PrecompilesValue = PrecompilesValue;
 	type ChainId = EVMChainId;
@@ -368,12 +370,27 @@ impl pallet_evm::Config for Runtime {
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type CreateAddressScheme = ();
 	type FindAuthor = FindAuthorTruncated<Aura>;
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type SuicideQuickClearLimit = SuicideQuickClearLimit;
+	type SuicideIdleClearLimit = SuicideIdleClearLimit;
 	type GasLimitStorageGrowthRatio = GasLimitStorageGrowthRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
+	type MaxCodeSize = ConstU32<{ 24 * 1024 }>;
+	type MaxInitCodeSize = ConstU32<{ 48 * 1024 }>;
+	type MaxRevertOutputLength = ConstU32<256>;
+	type CreateOriginFilter = frame_support::traits::Everything;
+	type CallOriginFilter = frame_support::traits::Everything;
+	type StorageDepositPerSlot = ();
+	type GasQuota = ();
+	type GasQuotaPeriod = ();
+	type PauseOrigin = frame_system::EnsureRoot<Self::AccountId>;
+
+	fn config() -> &'static pallet_evm::EvmConfig {
+		pallet_evm::Pallet::<Self>::hardfork().config()
+	}
 }
 
 parameter_types! {
@@ -385,6 +402,7 @@ impl pallet_ethereum::Config for Runtime {
 	type StateRoot = pallet_ethereum::IntermediateStateRoot<Self::Version>;
 	type PostLogContent = PostBlockAndTxnHashes;
 	type ExtraDataLength = ConstU32<30>;
+	type MaxNonceGap = ConstU64<64>;
 }
 
 parameter_types! {
@@ -414,6 +432,7 @@ impl pallet_base_fee::BaseFeeThreshold for BaseFeeThreshold {
 impl pallet_base_fee::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Threshold = BaseFeeThreshold;
+	type BlockUtilization = pallet_base_fee::BlockWeightUtilization<Self>;
 	type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
 	type DefaultElasticity = DefaultElasticity;
 }
@@ -778,6 +797,14 @@ impl_runtime_apis! {
 			pallet_evm::AccountCodes::<Runtime>::get(address)
 		}
 
+		fn account_code_at_paged(address: H160, offset: u32, len: u32) -> Vec<u8> {
+			pallet_evm::Pallet::<Runtime>::account_code_at_paged(address, offset, len)
+		}
+
+		fn code_by_hash(code_hash: H256) -> Option<Vec<u8>> {
+			pallet_evm::CodesByHash::<Runtime>::get(code_hash)
+		}
+
 		fn author() -> H160 {
 			<pallet_evm::Pallet<Runtime>>::find_author()
 		}
@@ -799,6 +826,7 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Option<Vec<(H160, pallet_evm::StateOverride)>>,
 		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
 			use pallet_evm::GasWeightMapping as _;
 
@@ -850,22 +878,32 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::call(
-				from,
-				to,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				false,
-				true,
-				weight_limit,
-				proof_size_base_cost,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
-			).map_err(|err| err.error.into())
+			pallet_evm::Pallet::<Runtime>::with_state_overrides(state_overrides.unwrap_or_default(), || {
+				if estimate {
+					pallet_evm::Pallet::<Runtime>::credit_estimate_balance(
+						from,
+						value,
+						gas_limit,
+						max_fee_per_gas,
+					);
+				}
+				<Runtime as pallet_evm::Config>::Runner::call(
+					from,
+					to,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
+				).map_err(|err| err.error.into())
+			})
 		}
 
 		fn create(
@@ -878,6 +916,7 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Option<Vec<(H160, pallet_evm::StateOverride)>>,
 		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
 			use pallet_evm::GasWeightMapping as _;
 
@@ -929,21 +968,31 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::create(
-				from,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				false,
-				true,
-				weight_limit,
-				proof_size_base_cost,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
-			).map_err(|err| err.error.into())
+			pallet_evm::Pallet::<Runtime>::with_state_overrides(state_overrides.unwrap_or_default(), || {
+				if estimate {
+					pallet_evm::Pallet::<Runtime>::credit_estimate_balance(
+						from,
+						value,
+						gas_limit,
+						max_fee_per_gas,
+					);
+				}
+				<Runtime as pallet_evm::Config>::Runner::create(
+					from,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
+				).map_err(|err| err.error.into())
+			})
 		}
 
 		fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
@@ -1013,6 +1062,21 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "evm-tracing")]
+	impl fp_rpc::EthereumTracingRuntimeApi<Block> for Runtime {
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction: &EthereumTransaction,
+			config: fp_evm::TraceConfig,
+		) -> Result<Vec<fp_evm::TraceStep>, sp_runtime::DispatchError> {
+			for extrinsic in extrinsics {
+				let _ = Executive::apply_extrinsic(extrinsic);
+			}
+
+			Ethereum::trace_transaction(transaction.clone(), &config)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (